@@ -0,0 +1,133 @@
+//! Compares the occupancy grid with scanning the snake, like apple positions
+//! and collisions used to be checked, on a 100x100 board with a 5000 segment
+//! snake.
+//!
+//! Run it with `cargo bench --bench occupancy`.
+
+use rand::{Rng, SeedableRng};
+use rand_pcg::Pcg32;
+use snake::game::Occupancy;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+use vek::Vec2;
+
+const BOARD_SIZE: i32 = 100;
+const SNAKE_LENGTH: i32 = 5000;
+const APPLE_COUNT: usize = 10;
+const ITERATIONS: u32 = 200;
+
+/// Lets the snake wind through the board row by row
+fn snake_positions() -> Vec<Vec2<i32>> {
+    (0..SNAKE_LENGTH)
+        .map(|index| {
+            let y = index / BOARD_SIZE;
+            let x = if y % 2 == 0 {
+                index % BOARD_SIZE
+            } else {
+                BOARD_SIZE - 1 - index % BOARD_SIZE
+            };
+
+            Vec2::new(x, y)
+        })
+        .collect()
+}
+
+fn apple_positions() -> Vec<Vec2<i32>> {
+    (0..APPLE_COUNT as i32)
+        .map(|index| Vec2::new(index * 3, BOARD_SIZE - 1))
+        .collect()
+}
+
+/// Apple placement as it was done before the occupancy grid
+fn choose_from_blacklist(
+    rng: &mut Pcg32,
+    snake: &[Vec2<i32>],
+    apples: &[Vec2<i32>],
+    next_head: Vec2<i32>,
+) -> Option<Vec2<i32>> {
+    let mut blacklist = vec![next_head];
+    blacklist.extend(snake.iter().cloned());
+    blacklist.extend(apples.iter().cloned());
+
+    let mut possible_positions = Vec::new();
+    for x in 0..BOARD_SIZE {
+        for y in 0..BOARD_SIZE {
+            let position = Vec2::new(x, y);
+            if !blacklist.contains(&position) {
+                possible_positions.push(position);
+            }
+        }
+    }
+
+    if possible_positions.is_empty() {
+        return None;
+    }
+    let index = rng.gen_range(0, possible_positions.len());
+
+    Some(possible_positions[index])
+}
+
+fn measure<F: FnMut()>(mut run: F) -> Duration {
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        run();
+    }
+
+    start.elapsed() / ITERATIONS
+}
+
+fn report(name: &str, scan: Duration, grid: Duration) {
+    println!(
+        "{:<16} scan: {:>12.3?}   grid: {:>12.3?}   ({:.0}x faster)",
+        name,
+        scan,
+        grid,
+        scan.as_secs_f64() / grid.as_secs_f64().max(1e-12)
+    );
+}
+
+fn main() {
+    let snake = snake_positions();
+    let apples = apple_positions();
+    let next_head = Vec2::new(0, SNAKE_LENGTH / BOARD_SIZE);
+    let mut rng = Pcg32::seed_from_u64(42);
+
+    let mut occupancy = Occupancy::new(BOARD_SIZE as u16, BOARD_SIZE as u16);
+    for position in snake.iter() {
+        occupancy.add_snake(position);
+    }
+    for position in apples.iter() {
+        occupancy.add_item(position);
+    }
+
+    println!(
+        "{} segments and {} apples on a {}x{} board, average of {} iterations",
+        SNAKE_LENGTH, APPLE_COUNT, BOARD_SIZE, BOARD_SIZE, ITERATIONS
+    );
+
+    let scan = measure(|| {
+        black_box(choose_from_blacklist(&mut rng, &snake, &apples, next_head));
+    });
+    let grid = measure(|| {
+        black_box(occupancy.choose_free_cell(&mut rng, &next_head));
+    });
+    report("apple placement", scan, grid);
+
+    let scan = measure(|| {
+        black_box(snake.contains(black_box(&next_head)));
+    });
+    let grid = measure(|| {
+        black_box(occupancy.is_on_snake(black_box(&next_head)));
+    });
+    report("collision check", scan, grid);
+
+    // Moving the snake only leaves the end of the tail and enters the new
+    // head, the scanning approach has nothing to update
+    let grid = measure(|| {
+        occupancy.remove_snake(&snake[snake.len() - 1]);
+        occupancy.add_snake(&next_head);
+        occupancy.remove_snake(&next_head);
+        occupancy.add_snake(&snake[snake.len() - 1]);
+    });
+    println!("{:<16} grid: {:>12.3?}", "move update", grid);
+}
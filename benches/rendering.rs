@@ -0,0 +1,141 @@
+//! Compares drawing a 5000 segment snake on a 100x100 board with a new
+//! texture per tile, like tiles used to be drawn, against the shared texture.
+//!
+//! Needs a display, run it with `cargo bench --bench rendering`.
+
+use snake::color::Color;
+use snake::resources;
+use std::time::{Duration, Instant};
+use tetra::graphics::{self, Canvas, DrawParams, Texture};
+use tetra::math::Vec2;
+use tetra::{Context, ContextBuilder};
+
+const BOARD_SIZE: i32 = 100;
+const SNAKE_LENGTH: i32 = 5000;
+const TILE_SIZE: f32 = 8.0;
+const FRAMES: u32 = 30;
+/// Fewer frames for the new textures, as each of them allocates one texture
+/// per tile
+const NEW_TEXTURE_FRAMES: u32 = 3;
+
+const TILE_COLOR: Color = Color::rgb(25, 200, 50);
+
+/// Lets the snake wind through the board row by row
+fn snake_positions() -> Vec<Vec2<i32>> {
+    (0..SNAKE_LENGTH)
+        .map(|index| {
+            let y = index / BOARD_SIZE;
+            let x = if y % 2 == 0 {
+                index % BOARD_SIZE
+            } else {
+                BOARD_SIZE - 1 - index % BOARD_SIZE
+            };
+
+            Vec2::new(x, y)
+        })
+        .collect()
+}
+
+fn tile_position(position: &Vec2<i32>) -> Vec2<f32> {
+    Vec2::new(position.x as f32 * TILE_SIZE, position.y as f32 * TILE_SIZE)
+}
+
+/// Allocates a texture for every tile like the old `Tile::draw`, which is
+/// flushed and freed again before the next tile
+fn draw_with_new_textures(ctx: &mut Context, positions: &[Vec2<i32>]) -> tetra::Result {
+    for position in positions {
+        let rectangle = Texture::from_rgba(ctx, 1, 1, &[255, 255, 255, 255])?;
+
+        graphics::draw(
+            ctx,
+            &rectangle,
+            DrawParams::new()
+                .scale(Vec2::new(TILE_SIZE, TILE_SIZE))
+                .position(tile_position(position))
+                .color(TILE_COLOR.as_tetra()),
+        );
+        graphics::flush(ctx);
+    }
+
+    Ok(())
+}
+
+fn draw_with_shared_texture(ctx: &mut Context, positions: &[Vec2<i32>]) -> tetra::Result {
+    for position in positions {
+        resources::draw_rectangle(
+            ctx,
+            tile_position(position),
+            Vec2::new(TILE_SIZE, TILE_SIZE),
+            &TILE_COLOR,
+        )?;
+    }
+
+    Ok(())
+}
+
+fn measure(
+    ctx: &mut Context,
+    canvas: &Canvas,
+    positions: &[Vec2<i32>],
+    frames: u32,
+    draw: fn(&mut Context, &[Vec2<i32>]) -> tetra::Result,
+) -> tetra::Result<Duration> {
+    let start = Instant::now();
+
+    for _ in 0..frames {
+        graphics::set_canvas(ctx, canvas);
+        graphics::clear(ctx, Color::rgb(0, 0, 0).as_tetra());
+        draw(ctx, positions)?;
+        graphics::reset_canvas(ctx);
+    }
+
+    Ok(start.elapsed() / frames)
+}
+
+fn main() -> tetra::Result {
+    let board_size = BOARD_SIZE * TILE_SIZE as i32;
+    let mut ctx = ContextBuilder::new("Rendering benchmark", board_size, board_size).build()?;
+    let canvas = Canvas::new(&mut ctx, board_size, board_size)?;
+    let positions = snake_positions();
+
+    // Warms up the shared texture and the driver
+    measure(
+        &mut ctx,
+        &canvas,
+        &positions,
+        FRAMES,
+        draw_with_shared_texture,
+    )?;
+
+    let new_textures = measure(
+        &mut ctx,
+        &canvas,
+        &positions,
+        NEW_TEXTURE_FRAMES,
+        draw_with_new_textures,
+    )?;
+    let shared_texture = measure(
+        &mut ctx,
+        &canvas,
+        &positions,
+        FRAMES,
+        draw_with_shared_texture,
+    )?;
+
+    println!(
+        "{} tiles on a {}x{} board",
+        SNAKE_LENGTH, BOARD_SIZE, BOARD_SIZE
+    );
+    println!(
+        "new texture per tile: {:>10.3?} per frame, average of {} frames",
+        new_textures, NEW_TEXTURE_FRAMES
+    );
+    println!(
+        "shared texture:       {:>10.3?} per frame, average of {} frames ({:.1}x faster)",
+        shared_texture,
+        FRAMES,
+        new_textures.as_secs_f64() / shared_texture.as_secs_f64()
+    );
+
+    Ok(())
+}
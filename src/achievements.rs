@@ -0,0 +1,263 @@
+use crate::game_mode::{EndReason, GameProgress};
+use crate::i18n;
+use crate::storage;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const FILE_NAME: &str = "achievements.json";
+
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum Achievement {
+    FirstApple,
+    Length25,
+    Length50,
+    Length100,
+    Survive5Minutes,
+    WinBoard,
+    AppleRush,
+    NoLeftTurns,
+}
+
+impl Achievement {
+    pub const ALL: [Achievement; 8] = [
+        Achievement::FirstApple,
+        Achievement::Length25,
+        Achievement::Length50,
+        Achievement::Length100,
+        Achievement::Survive5Minutes,
+        Achievement::WinBoard,
+        Achievement::AppleRush,
+        Achievement::NoLeftTurns,
+    ];
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            Achievement::FirstApple => "first-apple",
+            Achievement::Length25 => "length-25",
+            Achievement::Length50 => "length-50",
+            Achievement::Length100 => "length-100",
+            Achievement::Survive5Minutes => "survive-5-minutes",
+            Achievement::WinBoard => "win-board",
+            Achievement::AppleRush => "apple-rush",
+            Achievement::NoLeftTurns => "no-left-turns",
+        }
+    }
+
+    pub fn title(&self) -> String {
+        i18n::tr(&format!("achievement-{}", self.id()))
+    }
+
+    pub fn description(&self) -> String {
+        i18n::tr(&format!("achievement-{}-description", self.id()))
+    }
+}
+
+/// Things happening in a game that achievements are based on
+#[derive(Clone, Debug, PartialEq)]
+pub enum AchievementEvent {
+    AppleEaten { tick: u64 },
+    Turned { is_left_turn: bool },
+    Progressed(GameProgress),
+    GameEnded(EndReason, GameProgress),
+}
+
+/// Tracks a single game and tells which achievements it unlocks
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct AchievementTracker {
+    apple_ticks: VecDeque<u64>,
+    has_turned_left: bool,
+}
+
+impl AchievementTracker {
+    pub fn new() -> AchievementTracker {
+        AchievementTracker::default()
+    }
+
+    pub fn handle(&mut self, event: &AchievementEvent) -> Vec<Achievement> {
+        let mut achieved = Vec::new();
+
+        match event {
+            AchievementEvent::AppleEaten { tick } => {
+                achieved.push(Achievement::FirstApple);
+
+                self.apple_ticks.push_back(*tick);
+                while self.apple_ticks.len() > 3 {
+                    self.apple_ticks.pop_front();
+                }
+                if self.apple_ticks.len() == 3 && tick - self.apple_ticks[0] < 10 {
+                    achieved.push(Achievement::AppleRush);
+                }
+            }
+            AchievementEvent::Turned { is_left_turn } => {
+                self.has_turned_left |= is_left_turn;
+            }
+            AchievementEvent::Progressed(progress) => {
+                if progress.length >= 25 {
+                    achieved.push(Achievement::Length25);
+                }
+                if progress.length >= 50 {
+                    achieved.push(Achievement::Length50);
+                }
+                if progress.length >= 100 {
+                    achieved.push(Achievement::Length100);
+                }
+                if progress.elapsed_seconds >= 5.0 * 60.0 {
+                    achieved.push(Achievement::Survive5Minutes);
+                }
+            }
+            AchievementEvent::GameEnded(reason, progress) => {
+                if reason == &EndReason::Victory {
+                    achieved.push(Achievement::WinBoard);
+                }
+                if !self.has_turned_left && progress.score >= 10 {
+                    achieved.push(Achievement::NoLeftTurns);
+                }
+            }
+        }
+
+        achieved
+    }
+}
+
+/// Achievements that have been unlocked, per player
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+pub struct Achievements {
+    /// Unix timestamps of the unlocks by achievement id by player name
+    players: HashMap<String, HashMap<String, u64>>,
+}
+
+impl Achievements {
+    pub fn load(dir: &Path) -> Achievements {
+        storage::load(dir, FILE_NAME).unwrap_or_else(|err| {
+            eprintln!("Unable to load achievements: {}", err);
+            Achievements::default()
+        })
+    }
+
+    pub fn save(&self, dir: &Path) {
+        if let Err(err) = storage::save(dir, FILE_NAME, self) {
+            eprintln!("Unable to save achievements: {}", err);
+        }
+    }
+
+    pub fn is_unlocked(&self, player: &str, achievement: Achievement) -> bool {
+        self.players
+            .get(player)
+            .map(|unlocked| unlocked.contains_key(achievement.id()))
+            .unwrap_or(false)
+    }
+
+    /// Unlocks an achievement and returns whether it was locked before.
+    pub fn unlock(&mut self, player: &str, achievement: Achievement) -> bool {
+        if self.is_unlocked(player, achievement) {
+            return false;
+        }
+
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or(0);
+
+        self.players
+            .entry(player.to_string())
+            .or_default()
+            .insert(achievement.id().to_string(), timestamp);
+
+        true
+    }
+}
+
+/// Name under which the achievements of the current player are stored
+///
+/// Can be set with the `PLAYER_NAME` environment variable and defaults to the
+/// name of the system user.
+pub fn player_name() -> String {
+    ["PLAYER_NAME", "USER", "USERNAME"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|name| !name.is_empty())
+        .unwrap_or_else(|| "Player".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn progress(length: u32, elapsed_seconds: f64, score: u32) -> GameProgress {
+        GameProgress {
+            ticks: 0,
+            elapsed_seconds,
+            length,
+            score,
+        }
+    }
+
+    #[test]
+    fn test_apple_rush() {
+        let mut tracker = AchievementTracker::new();
+
+        assert_eq!(
+            tracker.handle(&AchievementEvent::AppleEaten { tick: 1 }),
+            vec![Achievement::FirstApple]
+        );
+        tracker.handle(&AchievementEvent::AppleEaten { tick: 5 });
+        assert!(!tracker
+            .handle(&AchievementEvent::AppleEaten { tick: 11 })
+            .contains(&Achievement::AppleRush));
+        assert!(tracker
+            .handle(&AchievementEvent::AppleEaten { tick: 14 })
+            .contains(&Achievement::AppleRush));
+    }
+
+    #[test]
+    fn test_progress() {
+        let mut tracker = AchievementTracker::new();
+
+        assert!(tracker
+            .handle(&AchievementEvent::Progressed(progress(24, 10.0, 23)))
+            .is_empty());
+        assert_eq!(
+            tracker.handle(&AchievementEvent::Progressed(progress(50, 300.0, 49))),
+            vec![
+                Achievement::Length25,
+                Achievement::Length50,
+                Achievement::Survive5Minutes
+            ]
+        );
+    }
+
+    #[test]
+    fn test_no_left_turns() {
+        let mut tracker = AchievementTracker::new();
+        tracker.handle(&AchievementEvent::Turned {
+            is_left_turn: false,
+        });
+        assert!(tracker
+            .handle(&AchievementEvent::GameEnded(
+                EndReason::Crashed,
+                progress(11, 20.0, 10)
+            ))
+            .contains(&Achievement::NoLeftTurns));
+
+        let mut tracker = AchievementTracker::new();
+        tracker.handle(&AchievementEvent::Turned { is_left_turn: true });
+        assert!(tracker
+            .handle(&AchievementEvent::GameEnded(
+                EndReason::Victory,
+                progress(11, 20.0, 10)
+            ))
+            .eq(&vec![Achievement::WinBoard]));
+    }
+
+    #[test]
+    fn test_unlock_per_player() {
+        let mut achievements = Achievements::default();
+
+        assert!(achievements.unlock("alice", Achievement::FirstApple));
+        assert!(!achievements.unlock("alice", Achievement::FirstApple));
+        assert!(achievements.is_unlocked("alice", Achievement::FirstApple));
+        assert!(!achievements.is_unlocked("bob", Achievement::FirstApple));
+    }
+}
@@ -1,4 +1,6 @@
+use crate::assets;
 use crate::config;
+use crate::resources;
 use crate::screen::Drawable;
 use tetra::graphics;
 use tetra::graphics::text::Font;
@@ -10,44 +12,23 @@ use tetra::Context;
 pub struct Alert {
     pub title: String,
     pub description: Option<String>,
-
-    font_builder: graphics::text::VectorFontBuilder,
-    title_font: Option<Font>,
-    description_font: Option<Font>,
 }
 
 impl Alert {
-    pub fn try_new<S: Into<String>, O: Into<Option<S>>>(
-        title: S,
-        description: O,
-    ) -> tetra::Result<Alert> {
-        Ok(Alert {
+    pub fn new<S: Into<String>, O: Into<Option<S>>>(title: S, description: O) -> Alert {
+        Alert {
             title: title.into(),
             description: description.into().map(|desc| desc.into()),
-
-            font_builder: graphics::text::VectorFontBuilder::new(
-                "./assets/fonts/digitalt/digitalt.ttf",
-            )?,
-            title_font: None,
-            description_font: None,
-        })
+        }
     }
 
     fn draw_background(ctx: &mut Context) -> tetra::Result {
-        let rectangle = graphics::Texture::from_rgba(ctx, 1, 1, &[255, 255, 255, 255])?;
-        graphics::draw(
+        resources::draw_rectangle(
             ctx,
-            &rectangle,
-            DrawParams::new()
-                .scale(Vec2::new(
-                    crate::WINDOW_WIDTH as f32,
-                    crate::WINDOW_HEIGHT as f32,
-                ))
-                .position(Vec2::new(0.0, 0.0))
-                .color(config::ALERT_BACKGROUND_COLOR.as_tetra()),
-        );
-
-        Ok(())
+            Vec2::new(0.0, 0.0),
+            Vec2::new(crate::WINDOW_WIDTH as f32, crate::WINDOW_HEIGHT as f32),
+            &config::ALERT_BACKGROUND_COLOR,
+        )
     }
 
     pub fn draw_text<T: Into<String>>(
@@ -81,20 +62,12 @@ impl Drawable for Alert {
     fn draw(&mut self, ctx: &mut Context) -> tetra::Result {
         Alert::draw_background(ctx)?;
 
-        if self.title_font.is_none() {
-            self.title_font = Some(self.font_builder.with_size(ctx, 56.0)?);
-        }
-        if let Some(title_font) = &self.title_font {
-            Alert::draw_text(ctx, &self.title, title_font.clone(), 100.0)?;
-        }
+        let title_font = assets::font(ctx, 56.0)?;
+        Alert::draw_text(ctx, &self.title, title_font, 100.0)?;
 
         if let Some(description) = &self.description {
-            if self.description_font.is_none() {
-                self.description_font = Some(self.font_builder.with_size(ctx, 16.0)?);
-            }
-            if let Some(description_font) = &self.description_font {
-                Alert::draw_text(ctx, description, description_font.clone(), 165.0)?;
-            }
+            let description_font = assets::font(ctx, 16.0)?;
+            Alert::draw_text(ctx, description, description_font, 165.0)?;
         }
 
         Ok(())
@@ -0,0 +1,161 @@
+//! Fonts and message catalogs used by the game
+//!
+//! The default assets are embedded into the binary, so the game can be started from any
+//! directory. Each of them can be overridden by a file with the same relative path in the
+//! `assets` folder of the data directory, e.g. `~/.local/share/snake/assets/locales/de.txt`.
+
+#[cfg(feature = "window")]
+use crate::i18n;
+use crate::storage;
+use std::borrow::Cow;
+#[cfg(feature = "window")]
+use std::cell::RefCell;
+#[cfg(feature = "window")]
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::{fs, io};
+#[cfg(feature = "window")]
+use tetra::graphics::text::{Font, VectorFontBuilder};
+#[cfg(feature = "window")]
+use tetra::Context;
+
+pub const DEFAULT_FONT: &str = "fonts/digitalt/digitalt.ttf";
+
+const EMBEDDED: &[(&str, &[u8])] = &[
+    (
+        DEFAULT_FONT,
+        include_bytes!("../assets/fonts/digitalt/digitalt.ttf"),
+    ),
+    ("locales/en.txt", include_bytes!("../assets/locales/en.txt")),
+    ("locales/de.txt", include_bytes!("../assets/locales/de.txt")),
+];
+
+#[cfg(feature = "window")]
+struct FontCache {
+    builder: VectorFontBuilder,
+    sizes: HashMap<u32, Font>,
+}
+
+#[cfg(feature = "window")]
+thread_local! {
+    static FONTS: RefCell<Option<FontCache>> = const { RefCell::new(None) };
+}
+
+/// Directory in which assets can be overridden
+pub fn override_dir() -> PathBuf {
+    storage::data_dir().join("assets")
+}
+
+pub fn embedded(path: &str) -> Option<&'static [u8]> {
+    EMBEDDED
+        .iter()
+        .find(|(embedded_path, _)| *embedded_path == path)
+        .map(|(_, data)| *data)
+}
+
+/// Loads an asset, preferring a file in the given override directory over the embedded one.
+///
+/// Absolute paths are read as they are.
+pub fn load_from(dir: &Path, path: &str) -> io::Result<Cow<'static, [u8]>> {
+    match fs::read(dir.join(path)) {
+        Ok(data) => Ok(Cow::Owned(data)),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {
+            embedded(path).map(Cow::Borrowed).ok_or(err)
+        }
+        Err(err) => Err(err),
+    }
+}
+
+pub fn load(path: &str) -> io::Result<Cow<'static, [u8]>> {
+    load_from(&override_dir(), path)
+}
+
+pub fn load_text(path: &str) -> io::Result<String> {
+    let data = load(path)?;
+
+    String::from_utf8(data.into_owned())
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
+/// Reads the font that covers the script of the selected language, falling back to the
+/// default font if it can't be read.
+///
+/// Can be overridden with the `SNAKE_FONT` environment variable.
+#[cfg(feature = "window")]
+fn font_data() -> &'static [u8] {
+    let result = match std::env::var_os("SNAKE_FONT") {
+        Some(path) => fs::read(&path).map(Cow::Owned),
+        None => load(&i18n::font()),
+    };
+
+    match result {
+        Ok(Cow::Borrowed(data)) => data,
+        // The font builder needs the data for the whole runtime and it's only read once
+        Ok(Cow::Owned(data)) => Box::leak(data.into_boxed_slice()),
+        Err(err) => {
+            eprintln!("Unable to load font: {}", err);
+            embedded(DEFAULT_FONT).expect("Default font is not embedded")
+        }
+    }
+}
+
+/// Returns the font of the given size. Fonts are created on first use and shared afterwards.
+#[cfg(feature = "window")]
+pub fn font(ctx: &mut Context, size: f32) -> tetra::Result<Font> {
+    FONTS.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        if cache.is_none() {
+            *cache = Some(FontCache {
+                builder: VectorFontBuilder::from_file_data(font_data())?,
+                sizes: HashMap::new(),
+            });
+        }
+        let cache = cache.as_mut().expect("Font cache is initialized");
+
+        if let Some(font) = cache.sizes.get(&size.to_bits()) {
+            return Ok(font.clone());
+        }
+
+        let font = cache.builder.with_size(ctx, size)?;
+        cache.sizes.insert(size.to_bits(), font.clone());
+
+        Ok(font)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("snake-assets-{}-{}", name, std::process::id()));
+        fs::create_dir_all(dir.join("locales")).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_load_embedded() {
+        let dir = temp_dir("embedded");
+
+        let catalog = load_from(&dir, "locales/de.txt").unwrap();
+        assert!(matches!(catalog, Cow::Borrowed(_)));
+        assert!(load_from(&dir, DEFAULT_FONT).is_ok());
+
+        let err = load_from(&dir, "locales/xx.txt").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_load_override() {
+        let dir = temp_dir("override");
+        fs::write(dir.join("locales/de.txt"), "app-title = Schlange").unwrap();
+
+        let catalog = load_from(&dir, "locales/de.txt").unwrap();
+        assert_eq!(&*catalog, b"app-title = Schlange");
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
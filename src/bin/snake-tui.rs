@@ -0,0 +1,24 @@
+// Copyright (C) Oliver Amann
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 as
+// published by the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+#[cfg(unix)]
+fn main() -> std::io::Result<()> {
+    snake::tui::run()
+}
+
+#[cfg(not(unix))]
+fn main() {
+    eprintln!("The terminal frontend is only available on Unix systems");
+    std::process::exit(1);
+}
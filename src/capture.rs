@@ -0,0 +1,294 @@
+//! Screenshots and frame recordings of the window
+//!
+//! tetra can't read back what it has drawn, so the frame is read with
+//! `glReadPixels` from the OpenGL context that SDL has made current.
+
+use crate::storage;
+use std::os::raw::{c_char, c_int, c_void};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Sender};
+use std::thread::{self, JoinHandle};
+use std::time::{SystemTime, UNIX_EPOCH};
+use std::{fs, io, mem};
+use tetra::{graphics, Context};
+
+const GL_RGBA: u32 = 0x1908;
+const GL_UNSIGNED_BYTE: u32 = 0x1401;
+
+type ReadPixels = unsafe extern "system" fn(c_int, c_int, c_int, c_int, u32, u32, *mut c_void);
+
+/// Which frames a recording keeps
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum RecordInterval {
+    Frame,
+    /// Only frames in which the game has moved on, which keeps recordings
+    /// short enough to be assembled into videos at the game's speed
+    Tick,
+}
+
+/// Image of the window in RGBA, from the top left to the bottom right
+pub struct Frame {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+impl Frame {
+    /// Reads what has been drawn so far in the current frame
+    pub fn read(ctx: &mut Context) -> Option<Frame> {
+        // Draw calls are batched, so some may not have been sent yet
+        graphics::flush(ctx);
+
+        unsafe {
+            let read_pixels = sdl2::sys::SDL_GL_GetProcAddress(
+                b"glReadPixels\0".as_ptr() as *const c_char,
+            );
+            let window = sdl2::sys::SDL_GL_GetCurrentWindow();
+            if read_pixels.is_null() || window.is_null() {
+                return None;
+            }
+            let read_pixels: ReadPixels = mem::transmute(read_pixels);
+
+            let (mut width, mut height) = (0, 0);
+            sdl2::sys::SDL_GL_GetDrawableSize(window, &mut width, &mut height);
+            if width <= 0 || height <= 0 {
+                return None;
+            }
+
+            let mut pixels = vec![0; width as usize * height as usize * 4];
+            read_pixels(
+                0,
+                0,
+                width,
+                height,
+                GL_RGBA,
+                GL_UNSIGNED_BYTE,
+                pixels.as_mut_ptr() as *mut c_void,
+            );
+            // OpenGL starts with the bottom row
+            flip_rows(&mut pixels, width as usize * 4);
+
+            Some(Frame {
+                width: width as u32,
+                height: height as u32,
+                pixels,
+            })
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> image::ImageResult<()> {
+        image::save_buffer(
+            path,
+            &self.pixels,
+            self.width,
+            self.height,
+            image::ColorType::Rgba8,
+        )
+    }
+}
+
+fn flip_rows(pixels: &mut [u8], row_length: usize) {
+    let rows = pixels.len() / row_length;
+
+    for row in 0..rows / 2 {
+        let (top, bottom) = pixels.split_at_mut((rows - row - 1) * row_length);
+        top[row * row_length..(row + 1) * row_length].swap_with_slice(&mut bottom[..row_length]);
+    }
+}
+
+/// Directory in which screenshots and recordings are stored
+pub fn screenshots_dir() -> PathBuf {
+    storage::data_dir().join("screenshots")
+}
+
+fn timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs())
+}
+
+fn frame_file_name(number: u32) -> String {
+    format!("frame-{:05}.png", number)
+}
+
+/// Encodes frames on another thread, so that a recording doesn't slow the
+/// game down
+struct FrameWriter {
+    sender: Option<Sender<(Frame, PathBuf)>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl FrameWriter {
+    fn new() -> FrameWriter {
+        let (sender, receiver) = mpsc::channel::<(Frame, PathBuf)>();
+        let thread = thread::spawn(move || {
+            for (frame, path) in receiver {
+                if let Err(err) = frame.save(&path) {
+                    eprintln!("Unable to save '{}': {}", path.display(), err);
+                }
+            }
+        });
+
+        FrameWriter {
+            sender: Some(sender),
+            thread: Some(thread),
+        }
+    }
+
+    fn write(&self, frame: Frame, path: PathBuf) {
+        if let Some(sender) = &self.sender {
+            let _ = sender.send((frame, path));
+        }
+    }
+}
+
+impl Drop for FrameWriter {
+    /// Finishes writing the queued frames
+    fn drop(&mut self) {
+        self.sender = None;
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+struct Recording {
+    dir: PathBuf,
+    interval: RecordInterval,
+    frames: u32,
+    last_tick: Option<u64>,
+}
+
+/// Takes screenshots and records frames on request
+pub struct Capture {
+    writer: FrameWriter,
+    recording: Option<Recording>,
+    is_screenshot_requested: bool,
+    screenshots: u32,
+}
+
+impl Capture {
+    pub fn new() -> Capture {
+        Capture {
+            writer: FrameWriter::new(),
+            recording: None,
+            is_screenshot_requested: false,
+            screenshots: 0,
+        }
+    }
+
+    /// Saves the next frame to the screenshots directory
+    pub fn request_screenshot(&mut self) {
+        self.is_screenshot_requested = true;
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recording.is_some()
+    }
+
+    /// Starts saving frames as numbered PNGs to the given directory
+    pub fn start_recording(&mut self, dir: PathBuf, interval: RecordInterval) -> io::Result<()> {
+        fs::create_dir_all(&dir)?;
+        println!("Recording frames to '{}'", dir.display());
+
+        self.recording = Some(Recording {
+            dir,
+            interval,
+            frames: 0,
+            last_tick: None,
+        });
+
+        Ok(())
+    }
+
+    /// Starts a recording in a new directory in the screenshots directory
+    pub fn start_new_recording(&mut self, interval: RecordInterval) -> io::Result<()> {
+        let dir = screenshots_dir().join(format!("recording-{}", timestamp()));
+
+        self.start_recording(dir, interval)
+    }
+
+    pub fn stop_recording(&mut self) {
+        if let Some(recording) = self.recording.take() {
+            println!(
+                "Recorded {} frames to '{}'",
+                recording.frames,
+                recording.dir.display()
+            );
+        }
+    }
+
+    /// Captures the frame that has just been drawn if it has been requested,
+    /// given the tick of the game that it shows
+    pub fn capture(&mut self, ctx: &mut Context, tick: u64) {
+        let is_recorded = match &self.recording {
+            Some(recording) => {
+                recording.interval == RecordInterval::Frame || recording.last_tick != Some(tick)
+            }
+            None => false,
+        };
+        if !is_recorded && !self.is_screenshot_requested {
+            return;
+        }
+
+        let frame = match Frame::read(ctx) {
+            Some(frame) => frame,
+            None => {
+                eprintln!("Unable to read the frame");
+                self.is_screenshot_requested = false;
+                return;
+            }
+        };
+
+        if mem::replace(&mut self.is_screenshot_requested, false) {
+            self.save_screenshot(&frame);
+        }
+
+        if let Some(recording) = self.recording.as_mut().filter(|_| is_recorded) {
+            recording.frames += 1;
+            recording.last_tick = Some(tick);
+            let path = recording.dir.join(frame_file_name(recording.frames));
+            self.writer.write(frame, path);
+        }
+    }
+
+    fn save_screenshot(&mut self, frame: &Frame) {
+        let dir = screenshots_dir();
+        self.screenshots += 1;
+        let path = dir.join(format!(
+            "screenshot-{}-{}.png",
+            timestamp(),
+            self.screenshots
+        ));
+
+        let result = fs::create_dir_all(&dir)
+            .map_err(image::ImageError::IoError)
+            .and_then(|_| frame.save(&path));
+        match result {
+            Ok(()) => println!("Saved screenshot to '{}'", path.display()),
+            Err(err) => eprintln!("Unable to save the screenshot: {}", err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flip_rows() {
+        let mut pixels = vec![1, 1, 2, 2, 3, 3];
+        flip_rows(&mut pixels, 2);
+        assert_eq!(pixels, vec![3, 3, 2, 2, 1, 1]);
+
+        let mut pixels = vec![1, 2, 3, 4];
+        flip_rows(&mut pixels, 2);
+        assert_eq!(pixels, vec![3, 4, 1, 2]);
+    }
+
+    #[test]
+    fn test_frame_file_names_sort_in_order() {
+        assert_eq!(frame_file_name(7), "frame-00007.png");
+        assert!(frame_file_name(9) < frame_file_name(10));
+    }
+}
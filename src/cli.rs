@@ -0,0 +1,541 @@
+//! Command line options of the game
+
+use crate::capture::RecordInterval;
+use crate::events::{EventStream, StreamEvent};
+use crate::game::{Level, LevelError, Replay, ReplayError};
+use crate::game_mode::GameMode;
+use crate::settings::{Settings, SettingsError};
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::{error, fmt};
+
+pub const USAGE: &str = "\
+Usage: snake [OPTIONS]
+       snake export-gif <REPLAY> <OUTPUT> [--tile-size <PIXELS>]
+
+Commands:
+  export-gif           Renders a replay to an animated GIF without a window
+
+Options:
+  --mode <MODE>        Mode to start with, e.g. endless, practice or time-attack=90
+  --board <WxH>        Size of the board in tiles, e.g. 20x12
+  --apples <COUNT>     Amount of simultaneously existing apples
+  --start-size <SIZE>  Initial size of the snake's tail
+  --speed <TILES>      Speed of the snake in tiles per second
+  --seed <SEED>        Seed for the placement and kinds of items
+  --countdown <SECS>   Seconds counted down before the snake moves, 0 to disable
+  --level <FILE>       Level with walls, which also sets the board size
+  --config <FILE>      JSON file with settings, e.g. {\"apple_count\": 3}
+  --replay <FILE>      Plays a recorded game back, e.g. last-replay.json
+  --headless           Plays the replay without a window and prints the result
+  --event-log <FILE>   Writes the events of the games as JSON Lines to the file
+  --fullscreen         Starts in fullscreen
+  --record <DIR>       Saves every frame as a numbered PNG to the directory
+  --record-ticks       Records only the frames in which the game moved on
+  --tile-size <PIXELS> Size of a tile in the GIF, 16 by default
+  -h, --help           Prints this help
+
+Options take precedence over the environment variables, which take precedence
+over the config file.
+";
+
+/// Options of a game to launch
+#[derive(Debug)]
+pub struct Options {
+    pub settings: Settings,
+    pub mode: GameMode,
+    pub replay: Option<Replay>,
+    pub headless: bool,
+    pub fullscreen: bool,
+    pub event_log: Option<PathBuf>,
+    pub record: Option<PathBuf>,
+    pub record_interval: RecordInterval,
+}
+
+#[derive(Debug)]
+pub enum Command {
+    Play(Box<Options>),
+    ExportGif {
+        replay: Box<Replay>,
+        output: PathBuf,
+        tile_size: u32,
+    },
+    Help,
+}
+
+const DEFAULT_GIF_TILE_SIZE: u32 = 16;
+const MAX_GIF_TILE_SIZE: u32 = 64;
+
+#[derive(Debug)]
+pub enum CliError {
+    UnknownOption(String),
+    MissingValue(String),
+    MissingArgument(&'static str),
+    InvalidValue {
+        option: String,
+        value: String,
+        reason: String,
+    },
+    /// An option that changes the game was combined with a replay, which
+    /// brings its own settings
+    ConflictsWithReplay(String),
+    HeadlessWithoutReplay,
+    Settings(SettingsError),
+    Level(PathBuf, LevelError),
+    Replay(PathBuf, ReplayError),
+}
+
+/// Options that are given as text, before they are parsed
+#[derive(Default)]
+struct Arguments {
+    mode: Option<String>,
+    board: Option<String>,
+    apples: Option<String>,
+    start_size: Option<String>,
+    speed: Option<String>,
+    seed: Option<String>,
+    countdown: Option<String>,
+    level: Option<String>,
+    config: Option<String>,
+    replay: Option<String>,
+    event_log: Option<String>,
+    record: Option<String>,
+    headless: bool,
+    fullscreen: bool,
+    record_ticks: bool,
+}
+
+impl Arguments {
+    fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Option<Arguments>, CliError> {
+        let mut arguments = Arguments::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            let (option, inline_value) = match arg.split_once('=') {
+                Some((option, value)) if option.starts_with("--") => {
+                    (option.to_string(), Some(value.to_string()))
+                }
+                _ => (arg, None),
+            };
+
+            let value = match option.as_str() {
+                "-h" | "--help" => return Ok(None),
+                "--headless" => {
+                    arguments.headless = true;
+                    continue;
+                }
+                "--fullscreen" => {
+                    arguments.fullscreen = true;
+                    continue;
+                }
+                "--record-ticks" => {
+                    arguments.record_ticks = true;
+                    continue;
+                }
+                "--mode" => &mut arguments.mode,
+                "--board" => &mut arguments.board,
+                "--apples" => &mut arguments.apples,
+                "--start-size" => &mut arguments.start_size,
+                "--speed" => &mut arguments.speed,
+                "--seed" => &mut arguments.seed,
+                "--countdown" => &mut arguments.countdown,
+                "--level" => &mut arguments.level,
+                "--config" => &mut arguments.config,
+                "--replay" => &mut arguments.replay,
+                "--event-log" => &mut arguments.event_log,
+                "--record" => &mut arguments.record,
+                _ => return Err(CliError::UnknownOption(option)),
+            };
+
+            *value = match inline_value.or_else(|| args.next()) {
+                Some(value) => Some(value),
+                None => return Err(CliError::MissingValue(option)),
+            };
+        }
+
+        Ok(Some(arguments))
+    }
+
+    fn record_interval(&self) -> RecordInterval {
+        if self.record_ticks {
+            RecordInterval::Tick
+        } else {
+            RecordInterval::Frame
+        }
+    }
+
+    /// Options that change the game, which can't be combined with a replay
+    fn game_options(&self) -> Vec<&'static str> {
+        [
+            ("--mode", self.mode.is_some()),
+            ("--board", self.board.is_some()),
+            ("--apples", self.apples.is_some()),
+            ("--start-size", self.start_size.is_some()),
+            ("--speed", self.speed.is_some()),
+            ("--seed", self.seed.is_some()),
+            ("--level", self.level.is_some()),
+            ("--config", self.config.is_some()),
+        ]
+        .iter()
+        .filter(|(_, is_given)| *is_given)
+        .map(|(option, _)| *option)
+        .collect()
+    }
+}
+
+fn parse_value<T: FromStr>(option: &str, value: &str) -> Result<T, CliError>
+where
+    T::Err: Display,
+{
+    value.parse().map_err(|err: T::Err| CliError::InvalidValue {
+        option: option.to_string(),
+        value: value.to_string(),
+        reason: err.to_string(),
+    })
+}
+
+fn parse_board(value: &str) -> Result<(u16, u16), CliError> {
+    let invalid = |reason: &str| CliError::InvalidValue {
+        option: "--board".to_string(),
+        value: value.to_string(),
+        reason: reason.to_string(),
+    };
+
+    let (width, height) = value
+        .split_once(['x', 'X'])
+        .ok_or_else(|| invalid("expected the format WxH, e.g. 20x12"))?;
+
+    Ok((
+        width.trim().parse().map_err(|_| invalid("invalid width"))?,
+        height
+            .trim()
+            .parse()
+            .map_err(|_| invalid("invalid height"))?,
+    ))
+}
+
+fn parse_export_gif<I: Iterator<Item = String>>(mut args: I) -> Result<Command, CliError> {
+    let mut paths = Vec::new();
+    let mut tile_size = None;
+
+    while let Some(arg) = args.next() {
+        let (option, inline_value) = match arg.split_once('=') {
+            Some((option, value)) if option.starts_with("--") => {
+                (option.to_string(), Some(value.to_string()))
+            }
+            _ => (arg, None),
+        };
+
+        match option.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "--tile-size" => match inline_value.or_else(|| args.next()) {
+                Some(value) => tile_size = Some(value),
+                None => return Err(CliError::MissingValue(option)),
+            },
+            _ if option.starts_with('-') => return Err(CliError::UnknownOption(option)),
+            _ => paths.push(PathBuf::from(option)),
+        }
+    }
+
+    let mut paths = paths.into_iter();
+    let replay_path = paths.next().ok_or(CliError::MissingArgument("REPLAY"))?;
+    let output = paths.next().ok_or(CliError::MissingArgument("OUTPUT"))?;
+    if let Some(path) = paths.next() {
+        return Err(CliError::UnknownOption(path.display().to_string()));
+    }
+
+    let tile_size = match tile_size {
+        Some(value) => {
+            let tile_size: u32 = parse_value("--tile-size", &value)?;
+            if tile_size == 0 || tile_size > MAX_GIF_TILE_SIZE {
+                return Err(CliError::InvalidValue {
+                    option: "--tile-size".to_string(),
+                    value,
+                    reason: format!("must be between 1 and {}", MAX_GIF_TILE_SIZE),
+                });
+            }
+            tile_size
+        }
+        None => DEFAULT_GIF_TILE_SIZE,
+    };
+
+    let replay = Replay::load(&replay_path).map_err(|err| CliError::Replay(replay_path, err))?;
+
+    Ok(Command::ExportGif {
+        replay: Box::new(replay),
+        output,
+        tile_size,
+    })
+}
+
+/// Parses the arguments of the program, without the program name
+pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Command, CliError> {
+    parse_with(args, Settings::from_env)
+}
+
+/// Parses the arguments on top of the player's settings, which are only read
+/// when they are needed
+fn parse_with<I, F>(args: I, player_settings: F) -> Result<Command, CliError>
+where
+    I: IntoIterator<Item = String>,
+    F: Fn() -> Result<Settings, SettingsError>,
+{
+    let mut args = args.into_iter().peekable();
+    if args.peek().map(String::as_str) == Some("export-gif") {
+        args.next();
+        return parse_export_gif(args);
+    }
+
+    let arguments = match Arguments::parse(args)? {
+        Some(arguments) => arguments,
+        None => return Ok(Command::Help),
+    };
+
+    let record_interval = arguments.record_interval();
+
+    if arguments.headless && arguments.replay.is_none() {
+        return Err(CliError::HeadlessWithoutReplay);
+    }
+
+    if let Some(path) = &arguments.replay {
+        if let Some(option) = arguments.game_options().first() {
+            return Err(CliError::ConflictsWithReplay(option.to_string()));
+        }
+
+        let path = PathBuf::from(path);
+        let replay = Replay::load(&path).map_err(|err| CliError::Replay(path, err))?;
+        // The language and countdown are the player's, not the ones of the
+        // recording
+        let player = player_settings().map_err(CliError::Settings)?;
+        let mut settings = Settings {
+            language: player.language,
+            countdown: player.countdown,
+            ..replay.settings.clone()
+        };
+        if let Some(countdown) = &arguments.countdown {
+            settings.countdown = parse_value("--countdown", countdown)?;
+        }
+        settings.validate().map_err(CliError::Settings)?;
+
+        return Ok(Command::Play(Box::new(Options {
+            settings,
+            mode: replay.mode,
+            replay: Some(replay),
+            headless: arguments.headless,
+            fullscreen: arguments.fullscreen,
+            event_log: arguments.event_log.map(PathBuf::from),
+            record: arguments.record.map(PathBuf::from),
+            record_interval,
+        })));
+    }
+
+    let mut settings = match &arguments.config {
+        Some(path) => Settings::from_file(Path::new(path)),
+        None => player_settings(),
+    }
+    .map_err(CliError::Settings)?;
+
+    if let Some(path) = &arguments.level {
+        let path = PathBuf::from(path);
+        let level = Level::load(&path).map_err(|err| CliError::Level(path, err))?;
+        settings.use_level(level);
+    }
+    if let Some(board) = &arguments.board {
+        let (width, height) = parse_board(board)?;
+        settings.board_width = width;
+        settings.board_height = height;
+    }
+    if let Some(apples) = &arguments.apples {
+        settings.apple_count = parse_value("--apples", apples)?;
+    }
+    if let Some(start_size) = &arguments.start_size {
+        settings.snake_start_size = parse_value("--start-size", start_size)?;
+    }
+    if let Some(speed) = &arguments.speed {
+        settings.speed = parse_value("--speed", speed)?;
+    }
+    if let Some(seed) = &arguments.seed {
+        settings.seed = Some(parse_value("--seed", seed)?);
+    }
+    if let Some(countdown) = &arguments.countdown {
+        settings.countdown = parse_value("--countdown", countdown)?;
+    }
+    settings.validate().map_err(CliError::Settings)?;
+
+    let mode = match &arguments.mode {
+        Some(mode) => parse_value("--mode", mode)?,
+        None => GameMode::default(),
+    };
+
+    Ok(Command::Play(Box::new(Options {
+        settings,
+        mode,
+        replay: None,
+        headless: false,
+        fullscreen: arguments.fullscreen,
+        event_log: arguments.event_log.map(PathBuf::from),
+        record: arguments.record.map(PathBuf::from),
+        record_interval,
+    })))
+}
+
+/// Plays a replay to its end and prints the result
+pub fn run_headless(replay: &Replay, events: &mut EventStream) {
+    let mut game = replay.start();
+    events.publish(StreamEvent::started(&game));
+    while !game.is_over() {
+        replay.queue_inputs(&mut game);
+        let update = game.update();
+        events.publish_game_events(&update, &game);
+    }
+
+    let reason = match game.end {
+        Some(reason) => reason,
+        None => return,
+    };
+    let summary = game.mode.summary(reason, &game.progress());
+
+    println!("{}", summary.title);
+    println!("{}: {}", summary.score_label, summary.score);
+    for detail in summary.details.iter() {
+        println!("{}", detail);
+    }
+    println!("seed: {}, ticks: {}", game.seed, game.ticks);
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CliError::UnknownOption(option) => write!(f, "unknown option '{}'", option),
+            CliError::MissingValue(option) => write!(f, "'{}' needs a value", option),
+            CliError::MissingArgument(name) => write!(f, "missing <{}>", name),
+            CliError::InvalidValue {
+                option,
+                value,
+                reason,
+            } => write!(f, "invalid value '{}' for '{}': {}", value, option, reason),
+            CliError::ConflictsWithReplay(option) => write!(
+                f,
+                "'{}' can't be combined with '--replay', which brings its own settings",
+                option
+            ),
+            CliError::HeadlessWithoutReplay => write!(f, "'--headless' needs a '--replay'"),
+            CliError::Settings(err) => write!(f, "{}", err),
+            CliError::Level(path, err) => {
+                write!(f, "unable to load level '{}': {}", path.display(), err)
+            }
+            CliError::Replay(path, err) => {
+                write!(f, "unable to load replay '{}': {}", path.display(), err)
+            }
+        }
+    }
+}
+
+impl error::Error for CliError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Parses the arguments on top of the default settings, so that the
+    /// environment of the tests doesn't matter
+    fn parse_args(args: &[&str]) -> Result<Command, CliError> {
+        parse_with(args.iter().map(|arg| arg.to_string()), || {
+            Ok(Settings::default())
+        })
+    }
+
+    #[test]
+    fn test_parse_options() {
+        let options = match parse_args(&[
+            "--board",
+            "20x12",
+            "--apples=3",
+            "--speed",
+            "4.5",
+            "--seed",
+            "7",
+            "--countdown",
+            "0",
+            "--mode",
+            "time-attack=90",
+            "--fullscreen",
+            "--event-log",
+            "events.jsonl",
+            "--record-ticks",
+        ]) {
+            Ok(Command::Play(options)) => options,
+            result => panic!("unexpected result {:?}", result),
+        };
+
+        assert_eq!(options.settings.board_width, 20);
+        assert_eq!(options.settings.board_height, 12);
+        assert_eq!(options.settings.apple_count, 3);
+        assert_eq!(options.settings.speed, 4.5);
+        assert_eq!(options.settings.seed, Some(7));
+        assert_eq!(options.settings.countdown, 0);
+        assert_eq!(options.mode, GameMode::TimeAttack { seconds: 90 });
+        assert!(options.fullscreen);
+        assert_eq!(options.event_log, Some(PathBuf::from("events.jsonl")));
+        assert_eq!(options.record, None);
+        assert_eq!(options.record_interval, RecordInterval::Tick);
+        assert!(!options.headless);
+    }
+
+    #[test]
+    fn test_parse_help() {
+        assert!(matches!(
+            parse_args(&["--apples", "2", "-h"]),
+            Ok(Command::Help)
+        ));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(matches!(
+            parse_args(&["--snake"]),
+            Err(CliError::UnknownOption(_))
+        ));
+        assert!(matches!(
+            parse_args(&["--apples"]),
+            Err(CliError::MissingValue(_))
+        ));
+        assert!(matches!(
+            parse_args(&["--apples", "many"]),
+            Err(CliError::InvalidValue { .. })
+        ));
+        assert!(matches!(
+            parse_args(&["--board", "20"]),
+            Err(CliError::InvalidValue { .. })
+        ));
+        assert!(matches!(
+            parse_args(&["--board", "2x2"]),
+            Err(CliError::Settings(_))
+        ));
+        assert!(matches!(
+            parse_args(&["--headless"]),
+            Err(CliError::HeadlessWithoutReplay)
+        ));
+        assert!(matches!(
+            parse_args(&["--replay", "last-replay.json", "--seed", "1"]),
+            Err(CliError::ConflictsWithReplay(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_export_gif_errors() {
+        assert!(matches!(
+            parse_args(&["export-gif", "replay.json"]),
+            Err(CliError::MissingArgument("OUTPUT"))
+        ));
+        assert!(matches!(
+            parse_args(&["export-gif", "replay.json", "out.gif", "--tile-size", "0"]),
+            Err(CliError::InvalidValue { .. })
+        ));
+        assert!(matches!(
+            parse_args(&["export-gif", "missing-replay.json", "out.gif"]),
+            Err(CliError::Replay(_, _))
+        ));
+    }
+}
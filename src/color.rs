@@ -1,6 +1,7 @@
+#[cfg(feature = "window")]
 use tetra::graphics::Color as TetraColor;
 
-#[derive(Clone, Default)]
+#[derive(Clone, Default, PartialEq, Debug)]
 pub struct Color {
     pub r: u8,
     pub g: u8,
@@ -21,6 +22,7 @@ impl Color {
         Color::rgba(0, 0, 0, 0.0)
     }
 
+    #[cfg(feature = "window")]
     pub fn as_tetra(&self) -> TetraColor {
         let mut tetra_color = TetraColor::rgb8(self.r, self.g, self.b);
         tetra_color.a = self.a;
@@ -29,6 +31,7 @@ impl Color {
     }
 }
 
+#[cfg(feature = "window")]
 impl From<TetraColor> for Color {
     fn from(tetra_color: TetraColor) -> Self {
         Color::rgba(
@@ -40,6 +43,7 @@ impl From<TetraColor> for Color {
     }
 }
 
+#[cfg(feature = "window")]
 impl From<Color> for TetraColor {
     fn from(color: Color) -> Self {
         let mut tetra_color = TetraColor::rgb8(color.r, color.g, color.b);
@@ -81,6 +85,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "window")]
     fn test_create_from_tetra() {
         let tetra_color = TetraColor::rgb8(100, 150, 200);
         let color = Color::from(tetra_color);
@@ -100,6 +105,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "window")]
     fn test_as_tetra() {
         let color = Color::rgba(10, 20, 30, 0.8);
         let tetra_color = color.as_tetra();
@@ -111,6 +117,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "window")]
     fn test_into_tetra() {
         let color = Color::rgba(10, 20, 30, 0.8);
         let tetra_color: TetraColor = color.into();
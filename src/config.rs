@@ -1,8 +1,11 @@
 use crate::color::Color;
+use crate::item::ItemTable;
 
 pub const TILE_SIZE: u16 = 50;
 pub const TILE_COUNT_X: u16 = 15;
 pub const TILE_COUNT_Y: u16 = 9;
+/// Share of a tile that is filled, leaving a margin to its neighbours
+pub const TILE_SCALE: f32 = 0.9;
 
 pub const SNAKE_HEAD_COLOR: Color = Color::rgb(5, 185, 190);
 pub const SNAKE_TAIL_COLOR: Color = Color::rgb(25, 200, 50);
@@ -14,3 +17,44 @@ pub const PLAYGROUND_WALL_COLOR: Color = Color::rgb(56, 56, 56);
 
 pub const ALERT_BACKGROUND_COLOR: Color = Color::rgba(0, 0, 0, 0.75);
 pub const ALERT_FONT_COLOR: Color = Color::rgb(255, 255, 255);
+/// Background of the selected item of a menu
+pub const MENU_SELECTION_COLOR: Color = Color::rgba(25, 200, 50, 0.6);
+
+pub const HUD_HEIGHT: u16 = 74;
+pub const HUD_BACKGROUND_COLOR: Color = Color::rgb(30, 30, 30);
+pub const HUD_LABEL_COLOR: Color = Color::rgb(160, 160, 160);
+pub const HUD_VALUE_COLOR: Color = Color::rgb(255, 255, 255);
+pub const HUD_EFFECT_COLOR: Color = Color::rgb(250, 200, 20);
+
+pub const UPDATES_PER_SECOND: f64 = 60.0;
+pub const SNAKE_SPEED: f64 = 3.0;
+/// Seconds counted down before the snake starts or continues moving
+pub const COUNTDOWN_SECONDS: u32 = 3;
+/// Amount of ticks that can be rewound in practice mode
+pub const REWIND_TICKS: usize = 45;
+pub const REWIND_UPDATES_PER_TICK: u32 = 6;
+
+pub const GOLDEN_APPLE_COLOR: Color = Color::rgb(250, 200, 20);
+pub const POISON_COLOR: Color = Color::rgb(130, 40, 160);
+pub const SPEED_UP_COLOR: Color = Color::rgb(250, 130, 20);
+pub const SLOW_DOWN_COLOR: Color = Color::rgb(40, 90, 230);
+pub const GHOST_COLOR: Color = Color::rgba(255, 255, 255, 0.6);
+pub const SHRINK_COLOR: Color = Color::rgb(230, 90, 170);
+
+/// Spawn weights in the order of `ItemKind::ALL`
+pub const ITEM_SPAWN_WEIGHTS: ItemTable = ItemTable::new([40, 3, 3, 2, 2, 2, 2]);
+/// Item lifetimes and effect durations in ticks in the order of `ItemKind::ALL`,
+/// where 0 means that the item stays until eaten or has no lasting effect
+pub const ITEM_DURATIONS: ItemTable = ItemTable::new([0, 30, 0, 20, 20, 25, 0]);
+
+pub const GOLDEN_APPLE_BONUS: u32 = 5;
+pub const POISON_PENALTY: u32 = 3;
+pub const SPEED_UP_FACTOR: f64 = 1.5;
+pub const SLOW_DOWN_FACTOR: f64 = 0.6;
+
+pub const TOAST_HEIGHT: u16 = 64;
+/// Seconds for which a toast is shown
+pub const TOAST_DURATION: f64 = 3.0;
+
+pub const ACHIEVEMENT_UNLOCKED_COLOR: Color = Color::rgb(255, 255, 255);
+pub const ACHIEVEMENT_LOCKED_COLOR: Color = Color::rgb(110, 110, 110);
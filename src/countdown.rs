@@ -0,0 +1,67 @@
+use crate::config;
+
+/// Seconds that are counted down before the snake starts or continues to
+/// move
+#[derive(Clone, Debug, PartialEq)]
+pub struct Countdown {
+    remaining_updates: u32,
+}
+
+impl Countdown {
+    pub fn new() -> Countdown {
+        Countdown {
+            remaining_updates: 0,
+        }
+    }
+
+    pub fn start(&mut self, seconds: u32) {
+        self.remaining_updates = seconds * config::UPDATES_PER_SECOND as u32;
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.remaining_updates == 0
+    }
+
+    /// Seconds that are left, rounded up so that the last one shows a 1
+    pub fn seconds_left(&self) -> u32 {
+        self.remaining_updates
+            .div_ceil(config::UPDATES_PER_SECOND as u32)
+    }
+
+    /// Counts one update down and returns whether the shown second changed
+    pub fn advance(&mut self) -> bool {
+        let seconds_left = self.seconds_left();
+        self.remaining_updates = self.remaining_updates.saturating_sub(1);
+
+        self.seconds_left() != seconds_left
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counts_seconds_down() {
+        let updates_per_second = config::UPDATES_PER_SECOND as u32;
+        let mut countdown = Countdown::new();
+        assert!(countdown.is_finished());
+
+        countdown.start(3);
+        assert_eq!(countdown.seconds_left(), 3);
+        assert!(!countdown.advance());
+        assert_eq!(countdown.seconds_left(), 3);
+
+        for _ in 1..updates_per_second - 1 {
+            assert!(!countdown.advance());
+        }
+        assert!(countdown.advance());
+        assert_eq!(countdown.seconds_left(), 2);
+
+        for _ in 0..2 * updates_per_second {
+            countdown.advance();
+        }
+        assert!(countdown.is_finished());
+        assert!(!countdown.advance());
+    }
+}
@@ -0,0 +1,421 @@
+use crate::game::Level;
+use crate::game_mode::EndReason;
+use crate::settings::Settings;
+use crate::storage;
+use rand::{Rng, SeedableRng};
+use rand_pcg::Pcg32;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+use vek::Vec2;
+
+const FILE_NAME: &str = "daily-challenges.json";
+/// Text file with the summary of the last recorded challenge, to be shared
+const SUMMARY_FILE_NAME: &str = "daily-summary.txt";
+
+/// Calendar day in UTC, so that players around the world get the same
+/// challenge at the same time
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Date {
+    pub year: i32,
+    pub month: u32,
+    pub day: u32,
+}
+
+impl Date {
+    pub fn today() -> Date {
+        let seconds = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or(0);
+
+        Date::from_days((seconds / 86_400) as i64)
+    }
+
+    /// Date of the day that is the given amount of days after 1970-01-01
+    pub fn from_days(days: i64) -> Date {
+        // Algorithm by Howard Hinnant, which counts in eras of 400 years
+        // that start in March
+        let days = days + 719_468;
+        let era = days.div_euclid(146_097);
+        let day_of_era = days.rem_euclid(146_097);
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let shifted_month = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+        let month = if shifted_month < 10 {
+            shifted_month + 3
+        } else {
+            shifted_month - 9
+        };
+        let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+        Date {
+            year: year as i32,
+            month: month as u32,
+            day: day as u32,
+        }
+    }
+
+    /// Days since 1970-01-01, the inverse of `from_days`
+    pub fn days(&self) -> i64 {
+        let (month, day) = (self.month as i64, self.day as i64);
+        let year = self.year as i64 - if month <= 2 { 1 } else { 0 };
+        let era = year.div_euclid(400);
+        let year_of_era = year.rem_euclid(400);
+        let shifted_month = if month > 2 { month - 3 } else { month + 9 };
+        let day_of_year = (153 * shifted_month + 2) / 5 + day - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+        era * 146_097 + day_of_era - 719_468
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+impl FromStr for Date {
+    type Err = String;
+
+    /// Parses a date in the format `YYYY-MM-DD`
+    fn from_str(s: &str) -> Result<Date, String> {
+        let invalid = || format!("invalid date '{}', expected YYYY-MM-DD", s);
+
+        let parts: Vec<&str> = s.split('-').collect();
+        let (year, month, day) = match parts.as_slice() {
+            [year, month, day] => (
+                year.parse().map_err(|_| invalid())?,
+                month.parse().map_err(|_| invalid())?,
+                day.parse().map_err(|_| invalid())?,
+            ),
+            _ => return Err(invalid()),
+        };
+        let date = Date { year, month, day };
+
+        // Days that don't exist, like the 30th of February, end up on
+        // another date
+        if Date::from_days(date.days()) != date {
+            return Err(invalid());
+        }
+
+        Ok(date)
+    }
+}
+
+/// Walls of the daily challenge, which keep the way of the snake up from its
+/// start free
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Layout {
+    Open,
+    Border,
+    Pillars,
+    Columns,
+}
+
+impl Layout {
+    const ALL: [Layout; 4] = [
+        Layout::Open,
+        Layout::Border,
+        Layout::Pillars,
+        Layout::Columns,
+    ];
+
+    fn level(&self, width: u16, height: u16) -> Option<Level> {
+        let (width, height) = (width as i32, height as i32);
+        let (left, right) = (width / 4, width - 1 - width / 4);
+        let (top, bottom) = (height / 4, height - 1 - height / 4);
+
+        let (name, walls): (&str, Vec<Vec2<i32>>) = match self {
+            Layout::Open => return None,
+            Layout::Border => (
+                "border",
+                (0..height)
+                    .flat_map(|y| (0..width).map(move |x| Vec2::new(x, y)))
+                    .filter(|cell| {
+                        cell.x == 0 || cell.y == 0 || cell.x == width - 1 || cell.y == height - 1
+                    })
+                    .collect(),
+            ),
+            Layout::Pillars => (
+                "pillars",
+                vec![
+                    Vec2::new(left, top),
+                    Vec2::new(right, top),
+                    Vec2::new(left, bottom),
+                    Vec2::new(right, bottom),
+                ],
+            ),
+            Layout::Columns => (
+                "columns",
+                (top..=bottom)
+                    .flat_map(|y| vec![Vec2::new(left, y), Vec2::new(right, y)])
+                    .collect(),
+            ),
+        };
+
+        Some(Level {
+            name: name.to_string(),
+            width: width as u16,
+            height: height as u16,
+            walls,
+            start: None,
+        })
+    }
+}
+
+/// Game of the day, whose seed, board, apples and walls are the same for
+/// every player
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct DailyChallenge {
+    /// Day of the challenge as `YYYY-MM-DD`
+    pub date: String,
+    pub seed: u64,
+    pub settings: Settings,
+}
+
+impl DailyChallenge {
+    /// Derives the challenge from the date. Only the language and the
+    /// countdown are taken from the player's settings.
+    pub fn new(date: Date, player_settings: &Settings) -> DailyChallenge {
+        let seed = mix(date.days() as u64);
+        let mut rng = Pcg32::seed_from_u64(seed);
+
+        let width = rng.gen_range(12, 25);
+        let height = rng.gen_range(8, 15);
+        let apple_count = rng.gen_range(1, 5);
+        let layout = Layout::ALL[rng.gen_range(0, Layout::ALL.len())];
+
+        let mut settings = Settings {
+            board_width: width,
+            board_height: height,
+            apple_count,
+            seed: Some(seed),
+            language: player_settings.language.clone(),
+            countdown: player_settings.countdown,
+            ..Settings::default()
+        };
+        if let Some(level) = layout.level(width, height) {
+            settings.use_level(level);
+        }
+
+        DailyChallenge {
+            date: date.to_string(),
+            seed,
+            settings,
+        }
+    }
+
+    pub fn today(player_settings: &Settings) -> DailyChallenge {
+        DailyChallenge::new(Date::today(), player_settings)
+    }
+
+    /// Whether a game has been played with the settings of the challenge,
+    /// regardless of the player's language and countdown
+    pub fn is_played_with(&self, settings: &Settings) -> bool {
+        settings.seed == Some(self.seed) && settings.key() == self.settings.key()
+    }
+
+    /// One line that tells others which challenge has been played and how
+    /// it went, e.g. to post it somewhere
+    pub fn summary(&self, result: &DailyResult) -> String {
+        let level = match &self.settings.level {
+            Some(level) => level.name.as_str(),
+            None => "open",
+        };
+
+        format!(
+            "snake daily {} seed={} board={}x{} apples={} level={} score={} ticks={}",
+            self.date,
+            self.seed,
+            self.settings.board_width,
+            self.settings.board_height,
+            self.settings.apple_count,
+            level,
+            result.score,
+            result.ticks
+        )
+    }
+}
+
+/// SplitMix64 finalizer, which spreads consecutive days over all seeds
+fn mix(value: u64) -> u64 {
+    let mut value = value.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    value = (value ^ (value >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    value = (value ^ (value >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+
+    value ^ (value >> 31)
+}
+
+/// Outcome of the first game of a daily challenge, which is the one that
+/// counts
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct DailyResult {
+    pub seed: u64,
+    pub score: u32,
+    pub ticks: u64,
+    /// How the game has ended, or none while it is played or once it has
+    /// been abandoned
+    pub reason: Option<EndReason>,
+}
+
+impl DailyResult {
+    /// Result of a game that has just started
+    pub fn new(challenge: &DailyChallenge) -> DailyResult {
+        DailyResult {
+            seed: challenge.seed,
+            score: 0,
+            ticks: 0,
+            reason: None,
+        }
+    }
+}
+
+/// Results of the daily challenges by their date
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+pub struct DailyResults {
+    results: BTreeMap<String, DailyResult>,
+}
+
+impl DailyResults {
+    pub fn load(dir: &Path) -> DailyResults {
+        storage::load(dir, FILE_NAME).unwrap_or_else(|err| {
+            eprintln!("Unable to load daily challenges: {}", err);
+            DailyResults::default()
+        })
+    }
+
+    pub fn save(&self, dir: &Path) {
+        if let Err(err) = storage::save(dir, FILE_NAME, self) {
+            eprintln!("Unable to save daily challenges: {}", err);
+        }
+    }
+
+    pub fn get(&self, challenge: &DailyChallenge) -> Option<&DailyResult> {
+        self.results.get(&challenge.date)
+    }
+
+    /// Uses up the attempt at the challenge as soon as a game of it starts,
+    /// unless it has been played before. Returns whether this game counts.
+    pub fn start(&mut self, challenge: &DailyChallenge) -> bool {
+        if self.results.contains_key(&challenge.date) {
+            return false;
+        }
+
+        self.results
+            .insert(challenge.date.clone(), DailyResult::new(challenge));
+
+        true
+    }
+
+    /// Replaces the result of the game that counts, as it goes on
+    pub fn update(&mut self, challenge: &DailyChallenge, result: DailyResult) {
+        self.results.insert(challenge.date.clone(), result);
+    }
+}
+
+/// Writes the summary into the data directory, so that it can be copied
+pub fn write_summary(dir: &Path, summary: &str) {
+    if let Err(err) = storage::write(dir, SUMMARY_FILE_NAME, &format!("{}\n", summary)) {
+        eprintln!(
+            "Unable to write the summary of the daily challenge: {}",
+            err
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dates() {
+        let dates = [
+            (0, "1970-01-01"),
+            (-1, "1969-12-31"),
+            (11_016, "2000-02-29"),
+            (20_745, "2026-10-19"),
+        ];
+        for (days, text) in dates.iter() {
+            let date: Date = text.parse().unwrap();
+            assert_eq!(date.days(), *days);
+            assert_eq!(Date::from_days(*days), date);
+            assert_eq!(date.to_string(), *text);
+        }
+
+        assert!("2026-02-29".parse::<Date>().is_err());
+        assert!("2026-13-01".parse::<Date>().is_err());
+        assert!("19.10.2026".parse::<Date>().is_err());
+    }
+
+    #[test]
+    fn test_challenge_depends_on_the_date() {
+        let date: Date = "2026-10-19".parse().unwrap();
+        let player = Settings {
+            language: "de".to_string(),
+            countdown: 0,
+            ..Settings::default()
+        };
+
+        let challenge = DailyChallenge::new(date, &Settings::default());
+        let other_player = DailyChallenge::new(date, &player);
+        assert_eq!(challenge.seed, other_player.seed);
+        assert!(challenge.is_played_with(&other_player.settings));
+        assert_eq!(other_player.settings.countdown, 0);
+
+        let next_day = DailyChallenge::new(Date::from_days(date.days() + 1), &player);
+        assert_ne!(challenge.seed, next_day.seed);
+        assert!(!challenge.is_played_with(&next_day.settings));
+    }
+
+    #[test]
+    fn test_challenges_are_playable() {
+        let start = "2026-01-01".parse::<Date>().unwrap().days();
+        for days in start..start + 366 {
+            let challenge = DailyChallenge::new(Date::from_days(days), &Settings::default());
+            let settings = &challenge.settings;
+            assert!(settings.validate().is_ok(), "{}", challenge.date);
+
+            // The snake starts upwards and must not run into a wall before
+            // the edge of the board
+            let start = settings.start_position();
+            assert!(
+                settings
+                    .walls()
+                    .iter()
+                    .all(|wall| wall.x != start.x || wall.y == 0 || wall.y > start.y),
+                "{}",
+                challenge.date
+            );
+        }
+    }
+
+    #[test]
+    fn test_start_once() {
+        let challenge = DailyChallenge::new(Date::from_days(20_000), &Settings::default());
+        let mut results = DailyResults::default();
+
+        assert!(results.start(&challenge));
+        assert_eq!(results.get(&challenge), Some(&DailyResult::new(&challenge)));
+
+        let result = DailyResult {
+            score: 12,
+            ticks: 340,
+            reason: Some(EndReason::Crashed),
+            ..DailyResult::new(&challenge)
+        };
+        results.update(&challenge, result.clone());
+        assert!(!results.start(&challenge));
+        assert_eq!(results.get(&challenge), Some(&result));
+        assert!(challenge.summary(&result).starts_with(&format!(
+            "snake daily {} seed={} ",
+            challenge.date, challenge.seed
+        )));
+    }
+}
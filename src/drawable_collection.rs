@@ -2,6 +2,7 @@ use crate::screen::Drawable;
 use std::ops::{Deref, DerefMut};
 use tetra::Context;
 
+#[derive(PartialEq, Debug)]
 pub struct DrawableCollection<T> {
     items: Vec<T>,
 }
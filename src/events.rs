@@ -0,0 +1,319 @@
+//! Stream of what happens in a game, for tools that analyze sessions or
+//! drive overlays without patching the game
+
+use crate::game::{Direction, Game, GameEvent};
+use crate::game_mode::{EndReason, GameMode};
+use crate::item::ItemKind;
+use crate::settings::Settings;
+use crate::statistics::DeathCause;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use vek::Vec2;
+
+/// Event of the stream, serialized with its name in the `event` field, e.g.
+/// `{"event":"paused","tick":12}`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "event", rename_all = "kebab-case")]
+pub enum StreamEvent {
+    GameStarted {
+        seed: u64,
+        mode: GameMode,
+        settings: Settings,
+    },
+    /// The snake moved forward by one tile
+    Tick {
+        tick: u64,
+        head: Vec2<i32>,
+        length: u32,
+        score: u32,
+    },
+    DirectionChanged {
+        tick: u64,
+        from: Direction,
+        to: Direction,
+    },
+    /// An item has been eaten, which are all apples of some kind
+    AppleEaten {
+        tick: u64,
+        kind: ItemKind,
+        position: Vec2<i32>,
+        new_position: Option<Vec2<i32>>,
+    },
+    AppleSpawned {
+        tick: u64,
+        kind: ItemKind,
+        position: Vec2<i32>,
+    },
+    Paused {
+        tick: u64,
+    },
+    Resumed {
+        tick: u64,
+    },
+    GameOver {
+        tick: u64,
+        reason: EndReason,
+        cause: Option<DeathCause>,
+        score: u32,
+    },
+}
+
+impl StreamEvent {
+    pub fn started(game: &Game) -> StreamEvent {
+        StreamEvent::GameStarted {
+            seed: game.seed,
+            mode: game.mode,
+            settings: game.settings.clone(),
+        }
+    }
+
+    /// Translates an event of the rules, given the game after the update
+    /// that caused it and the tick of that update
+    pub fn from_game_event(event: &GameEvent, tick: u64, game: &Game) -> StreamEvent {
+        match event {
+            GameEvent::Turned { from, to } => StreamEvent::DirectionChanged {
+                tick,
+                from: *from,
+                to: *to,
+            },
+            GameEvent::ItemEaten {
+                kind,
+                position,
+                new_position,
+                ..
+            } => StreamEvent::AppleEaten {
+                tick,
+                kind: *kind,
+                position: *position,
+                new_position: *new_position,
+            },
+            GameEvent::ItemSpawned { kind, position } => StreamEvent::AppleSpawned {
+                tick,
+                kind: *kind,
+                position: *position,
+            },
+            GameEvent::Moved => StreamEvent::Tick {
+                tick,
+                head: game.snake.head,
+                length: game.progress().length,
+                score: game.score,
+            },
+            GameEvent::Ended { reason, death } => StreamEvent::GameOver {
+                tick,
+                reason: *reason,
+                cause: death.as_ref().map(|death| death.cause),
+                score: game.score,
+            },
+        }
+    }
+}
+
+/// Receives the events of a stream
+pub trait Subscriber {
+    fn handle(&mut self, event: &StreamEvent);
+}
+
+impl<F: FnMut(&StreamEvent)> Subscriber for F {
+    fn handle(&mut self, event: &StreamEvent) {
+        self(event)
+    }
+}
+
+/// Passes events on to all of its subscribers
+#[derive(Default)]
+pub struct EventStream {
+    subscribers: Vec<Box<dyn Subscriber>>,
+}
+
+impl EventStream {
+    /// Creates a stream that logs to the given file, if there is one
+    pub fn with_log(path: Option<&Path>) -> EventStream {
+        let mut stream = EventStream::default();
+
+        if let Some(path) = path {
+            match JsonLinesLog::create(path) {
+                Ok(log) => stream.subscribe(log),
+                Err(err) => eprintln!(
+                    "Unable to create the event log '{}': {}",
+                    path.display(),
+                    err
+                ),
+            }
+        }
+
+        stream
+    }
+
+    pub fn subscribe<S: Subscriber + 'static>(&mut self, subscriber: S) {
+        self.subscribers.push(Box::new(subscriber));
+    }
+
+    pub fn publish(&mut self, event: StreamEvent) {
+        for subscriber in self.subscribers.iter_mut() {
+            subscriber.handle(&event);
+        }
+    }
+
+    pub fn publish_game_events(&mut self, events: &[GameEvent], game: &Game) {
+        if self.subscribers.is_empty() {
+            return;
+        }
+
+        // All events of an update share its tick, even those that the rules
+        // emit before the tick is counted
+        let tick = game.ticks;
+        for event in events {
+            self.publish(StreamEvent::from_game_event(event, tick, game));
+        }
+    }
+}
+
+/// Writes every event as a line of JSON
+pub struct JsonLinesLog<W: Write> {
+    writer: W,
+    has_failed: bool,
+}
+
+impl JsonLinesLog<BufWriter<File>> {
+    pub fn create(path: &Path) -> io::Result<JsonLinesLog<BufWriter<File>>> {
+        Ok(JsonLinesLog::new(BufWriter::new(File::create(path)?)))
+    }
+}
+
+impl<W: Write> JsonLinesLog<W> {
+    pub fn new(writer: W) -> JsonLinesLog<W> {
+        JsonLinesLog {
+            writer,
+            has_failed: false,
+        }
+    }
+
+    fn write(&mut self, event: &StreamEvent) -> io::Result<()> {
+        serde_json::to_writer(&mut self.writer, event)?;
+        self.writer.write_all(b"\n")?;
+        // Lets other tools follow the log while the game is running
+        self.writer.flush()
+    }
+}
+
+impl<W: Write> Subscriber for JsonLinesLog<W> {
+    fn handle(&mut self, event: &StreamEvent) {
+        if self.has_failed {
+            return;
+        }
+
+        if let Err(err) = self.write(event) {
+            eprintln!("Unable to log game event: {}", err);
+            self.has_failed = true;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_subscribers_receive_game_events() {
+        let mut game = Game::new(GameMode::Endless, Settings::default());
+        let received = Rc::new(RefCell::new(Vec::new()));
+        let mut stream = EventStream::default();
+        let sink = Rc::clone(&received);
+        stream.subscribe(move |event: &StreamEvent| sink.borrow_mut().push(event.clone()));
+
+        stream.publish(StreamEvent::started(&game));
+        while !game.is_over() {
+            let events = game.update();
+            stream.publish_game_events(&events, &game);
+        }
+
+        let received = received.borrow();
+        assert!(matches!(
+            received.first(),
+            Some(StreamEvent::GameStarted { seed, .. }) if *seed == game.seed
+        ));
+        let ticks = received
+            .iter()
+            .filter(|event| matches!(event, StreamEvent::Tick { .. }))
+            .count();
+        assert_eq!(ticks as u64, game.ticks);
+        assert!(matches!(
+            received.last(),
+            Some(StreamEvent::GameOver {
+                reason: EndReason::Crashed,
+                cause: Some(DeathCause::Wall),
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_events_of_an_update_share_the_tick() {
+        let board = "
+            direction: left
+            ....
+            .*..
+            .Ho.
+            ....
+            ";
+        let mut game = Game::from_board(
+            GameMode::Endless,
+            Settings::default(),
+            &board.parse().unwrap(),
+        );
+        let received = Rc::new(RefCell::new(Vec::new()));
+        let mut stream = EventStream::default();
+        let sink = Rc::clone(&received);
+        stream.subscribe(move |event: &StreamEvent| sink.borrow_mut().push(event.clone()));
+
+        // Turns towards the apple, eats it and spawns a new one in one update
+        game.queue_direction(Direction::Up);
+        loop {
+            let events = game.update();
+            stream.publish_game_events(&events, &game);
+            if events.contains(&GameEvent::Moved) {
+                break;
+            }
+        }
+
+        let received = received.borrow();
+        assert_eq!(received.len(), 4, "{:?}", received);
+        for event in received.iter() {
+            let tick = match event {
+                StreamEvent::Tick { tick, .. }
+                | StreamEvent::DirectionChanged { tick, .. }
+                | StreamEvent::AppleEaten { tick, .. }
+                | StreamEvent::AppleSpawned { tick, .. } => *tick,
+                event => panic!("unexpected event {:?}", event),
+            };
+            assert_eq!(tick, 1, "{:?}", event);
+        }
+    }
+
+    #[test]
+    fn test_json_lines_log() {
+        let mut log = JsonLinesLog::new(Vec::new());
+        log.handle(&StreamEvent::Paused { tick: 12 });
+        log.handle(&StreamEvent::DirectionChanged {
+            tick: 13,
+            from: Direction::Up,
+            to: Direction::Left,
+        });
+
+        let output = String::from_utf8(log.writer).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines[0], r#"{"event":"paused","tick":12}"#);
+        assert_eq!(
+            serde_json::from_str::<StreamEvent>(lines[1]).unwrap(),
+            StreamEvent::DirectionChanged {
+                tick: 13,
+                from: Direction::Up,
+                to: Direction::Left,
+            }
+        );
+    }
+}
@@ -0,0 +1,130 @@
+//! Exports of replays that are rendered without a window
+
+use crate::config;
+use crate::game::Replay;
+use crate::render::{BoardRenderer, Image};
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+/// How long the last frame is shown before the animation starts over, in
+/// hundredths of a second
+const FINAL_FRAME_DELAY: u16 = 200;
+
+/// Converts an image to the indices of a palette, which GIFs need. The
+/// board only has a handful of colors, so they can be kept exactly.
+fn index_colors(image: &Image) -> (Vec<u8>, Vec<u8>) {
+    let mut palette = Vec::new();
+    let mut indices = HashMap::new();
+
+    let pixels = image
+        .pixels
+        .chunks_exact(4)
+        .map(|pixel| {
+            let color = [pixel[0], pixel[1], pixel[2]];
+            *indices.entry(color).or_insert_with(|| {
+                palette.extend_from_slice(&color);
+                // Boards have fewer colors than fit into a palette, later
+                // ones share the last entry
+                (palette.len() / 3 - 1).min(255) as u8
+            })
+        })
+        .collect();
+    palette.truncate(256 * 3);
+
+    (pixels, palette)
+}
+
+/// Time at which the game has made the given amount of updates, in
+/// hundredths of a second
+fn centiseconds(updates: u64) -> u64 {
+    (updates as f64 * 100.0 / config::UPDATES_PER_SECOND).round() as u64
+}
+
+/// Writes frames of the board to a looping GIF
+struct GifWriter<W: Write> {
+    encoder: gif::Encoder<W>,
+}
+
+impl<W: Write> GifWriter<W> {
+    fn new(writer: W, width: u32, height: u32) -> io::Result<GifWriter<W>> {
+        let too_large = || io::Error::new(io::ErrorKind::InvalidInput, "the GIF is too large");
+        let width = u16::try_from(width).map_err(|_| too_large())?;
+        let height = u16::try_from(height).map_err(|_| too_large())?;
+
+        let mut encoder = gif::Encoder::new(writer, width, height, &[])?;
+        gif::SetParameter::set(&mut encoder, gif::Repeat::Infinite)?;
+
+        Ok(GifWriter { encoder })
+    }
+
+    fn write(&mut self, image: &Image, delay: u16) -> io::Result<()> {
+        let (pixels, palette) = index_colors(image);
+        let mut frame = gif::Frame::from_palette_pixels(
+            image.width as u16,
+            image.height as u16,
+            &pixels,
+            &palette,
+            None,
+        );
+        frame.delay = delay;
+
+        self.encoder.write_frame(&frame)
+    }
+}
+
+/// Renders every tick of a replay to an animated GIF that plays at the speed
+/// of the game, and returns the amount of frames
+pub fn export_gif(replay: &Replay, path: &Path, tile_size: u32) -> io::Result<u32> {
+    let mut game = replay.start();
+    let renderer = BoardRenderer::with_tile_size(&game.settings, tile_size);
+    let file = BufWriter::new(File::create(path)?);
+    let mut writer = GifWriter::new(file, renderer.width, renderer.height)?;
+
+    let mut frames = 0;
+    let mut image = renderer.render(&game);
+    let mut shown_at = 0;
+    while !game.is_over() {
+        let ticks = game.ticks;
+        replay.queue_inputs(&mut game);
+        game.update();
+        if game.ticks == ticks && !game.is_over() {
+            continue;
+        }
+
+        // A frame is shown until the game has moved on
+        let now = centiseconds(game.updates());
+        writer.write(&image, (now - shown_at).min(u16::MAX as u64) as u16)?;
+        frames += 1;
+        shown_at = now;
+        image = renderer.render(&game);
+    }
+    writer.write(&image, FINAL_FRAME_DELAY)?;
+
+    Ok(frames + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::Color;
+
+    #[test]
+    fn test_index_colors() {
+        let mut image = Image::new(3, 1, &Color::rgb(1, 2, 3));
+        image.pixels[4..8].copy_from_slice(&[9, 8, 7, 255]);
+
+        let (pixels, palette) = index_colors(&image);
+        assert_eq!(pixels, vec![0, 1, 0]);
+        assert_eq!(palette, vec![1, 2, 3, 9, 8, 7]);
+    }
+
+    #[test]
+    fn test_centiseconds() {
+        assert_eq!(centiseconds(0), 0);
+        assert_eq!(centiseconds(20), 33);
+        assert_eq!(centiseconds(60), 100);
+    }
+}
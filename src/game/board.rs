@@ -0,0 +1,378 @@
+use super::{Direction, Game, Item, Snake};
+use crate::item::ItemKind;
+use std::str::FromStr;
+use std::{error, fmt};
+use vek::Vec2;
+
+/// What is on the board of a game, which can be written as text and parsed
+/// back, e.g. to set up games in tests:
+///
+/// ```text
+/// direction: up
+/// .....
+/// .H.*.
+/// .o...
+/// .oo#.
+/// ```
+///
+/// `H` is the head of the snake and `o` its tail, which is followed from the
+/// part behind the head to its end. `#` is a wall, `*` an apple and the other
+/// items are `$` golden apple, `x` poison, `+` speed up, `-` slow down, `?`
+/// ghost and `~` shrink.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Board {
+    pub width: u16,
+    pub height: u16,
+    pub snake: Snake,
+    pub items: Vec<Item>,
+    pub walls: Vec<Vec2<i32>>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum BoardError {
+    /// The first line doesn't name the direction of the snake
+    MissingDirection,
+    UnknownDirection(String),
+    Empty,
+    UnknownCell {
+        cell: char,
+        row: usize,
+        column: usize,
+    },
+    MissingHead,
+    MultipleHeads,
+    /// The tail parts can be connected in more than one order
+    AmbiguousTail,
+    /// Parts of the tail that aren't connected to the snake
+    DetachedTail(Vec2<i32>),
+}
+
+const HEAD: char = 'H';
+const TAIL: char = 'o';
+const WALL: char = '#';
+const EMPTY: char = '.';
+
+fn item_cell(kind: ItemKind) -> char {
+    match kind {
+        ItemKind::Apple => '*',
+        ItemKind::GoldenApple => '$',
+        ItemKind::Poison => 'x',
+        ItemKind::SpeedUp => '+',
+        ItemKind::SlowDown => '-',
+        ItemKind::Ghost => '?',
+        ItemKind::Shrink => '~',
+    }
+}
+
+fn direction_name(direction: Direction) -> &'static str {
+    match direction {
+        Direction::Up => "up",
+        Direction::Down => "down",
+        Direction::Left => "left",
+        Direction::Right => "right",
+    }
+}
+
+impl Board {
+    pub fn of(game: &Game) -> Board {
+        Board {
+            width: game.settings.board_width,
+            height: game.settings.board_height,
+            snake: game.snake.clone(),
+            items: game.apples.clone(),
+            walls: game.settings.walls().to_vec(),
+        }
+    }
+
+    fn cell(&self, position: Vec2<i32>) -> char {
+        if self.snake.head == position {
+            HEAD
+        } else if self.snake.tail.contains(&position) {
+            TAIL
+        } else if let Some(item) = self.items.iter().find(|item| item.position == position) {
+            item_cell(item.kind)
+        } else if self.walls.contains(&position) {
+            WALL
+        } else {
+            EMPTY
+        }
+    }
+
+    /// Follows the tail from the head to its end, starting behind the head.
+    /// The order has to be the only one that connects all parts.
+    fn follow_tail(
+        head: Vec2<i32>,
+        direction: Direction,
+        parts: &[Vec2<i32>],
+    ) -> Result<Vec<Vec2<i32>>, BoardError> {
+        let mut search = TailSearch {
+            parts,
+            is_used: vec![false; parts.len()],
+            path: Vec::new(),
+            longest: Vec::new(),
+            solutions: Vec::new(),
+            budget: TAIL_SEARCH_BUDGET,
+        };
+
+        // The first part of the tail is where the head has been before
+        let mut behind = Snake::new(head);
+        behind.direction = direction;
+        behind.grow_tail();
+        match parts.iter().position(|part| *part == behind.tail[0]) {
+            Some(index) => search.visit(index),
+            None => search.follow(head),
+        }
+
+        match search.solutions.len() {
+            1 => Ok(search.solutions.remove(0)),
+            0 if search.budget > 0 => {
+                let detached = parts
+                    .iter()
+                    .find(|part| !search.longest.contains(part))
+                    .copied()
+                    .unwrap_or(head);
+                Err(BoardError::DetachedTail(detached))
+            }
+            _ => Err(BoardError::AmbiguousTail),
+        }
+    }
+}
+
+/// Amount of steps after which the search for the order of the tail gives up,
+/// e.g. on large blocks of tail parts
+const TAIL_SEARCH_BUDGET: u32 = 100_000;
+
+/// Depth-first search for the orders in which the tail parts can be
+/// connected, which stops as soon as there is more than one
+struct TailSearch<'a> {
+    parts: &'a [Vec2<i32>],
+    is_used: Vec<bool>,
+    path: Vec<Vec2<i32>>,
+    longest: Vec<Vec2<i32>>,
+    solutions: Vec<Vec<Vec2<i32>>>,
+    budget: u32,
+}
+
+impl TailSearch<'_> {
+    fn visit(&mut self, index: usize) {
+        self.is_used[index] = true;
+        self.path.push(self.parts[index]);
+        self.follow(self.parts[index]);
+        self.path.pop();
+        self.is_used[index] = false;
+    }
+
+    fn follow(&mut self, current: Vec2<i32>) {
+        if self.path.len() > self.longest.len() {
+            self.longest = self.path.clone();
+        }
+        if self.path.len() == self.parts.len() {
+            self.solutions.push(self.path.clone());
+            return;
+        }
+
+        for index in 0..self.parts.len() {
+            if self.solutions.len() > 1 || self.budget == 0 {
+                return;
+            }
+
+            let distance = self.parts[index] - current;
+            if !self.is_used[index] && distance.x.abs() + distance.y.abs() == 1 {
+                self.budget -= 1;
+                self.visit(index);
+            }
+        }
+    }
+}
+
+impl fmt::Display for Board {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "direction: {}", direction_name(self.snake.direction))?;
+
+        for y in 0..self.height as i32 {
+            let row: String = (0..self.width as i32)
+                .map(|x| self.cell(Vec2::new(x, y)))
+                .collect();
+            writeln!(f, "{}", row)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for Board {
+    type Err = BoardError;
+
+    fn from_str(content: &str) -> Result<Board, BoardError> {
+        let mut lines = content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty());
+
+        let direction = match lines.next().and_then(|line| line.strip_prefix("direction:")) {
+            Some(direction) => match direction.trim() {
+                "up" => Direction::Up,
+                "down" => Direction::Down,
+                "left" => Direction::Left,
+                "right" => Direction::Right,
+                other => return Err(BoardError::UnknownDirection(other.to_string())),
+            },
+            None => return Err(BoardError::MissingDirection),
+        };
+
+        let rows: Vec<&str> = lines.collect();
+        if rows.is_empty() {
+            return Err(BoardError::Empty);
+        }
+
+        let mut head = None;
+        let mut tail = Vec::new();
+        let mut items = Vec::new();
+        let mut walls = Vec::new();
+        for (y, row) in rows.iter().enumerate() {
+            for (x, cell) in row.chars().enumerate() {
+                let position = Vec2::new(x as i32, y as i32);
+                let kind = ItemKind::ALL
+                    .iter()
+                    .find(|kind| item_cell(**kind) == cell);
+
+                match cell {
+                    HEAD if head.is_some() => return Err(BoardError::MultipleHeads),
+                    HEAD => head = Some(position),
+                    TAIL => tail.push(position),
+                    WALL => walls.push(position),
+                    EMPTY => {}
+                    _ => match kind {
+                        Some(kind) => items.push(Item::new(*kind, position, 0)),
+                        None => {
+                            return Err(BoardError::UnknownCell {
+                                cell,
+                                row: y + 1,
+                                column: x + 1,
+                            })
+                        }
+                    },
+                }
+            }
+        }
+
+        let head = head.ok_or(BoardError::MissingHead)?;
+        let tail = Board::follow_tail(head, direction, &tail)?;
+
+        Ok(Board {
+            width: rows
+                .iter()
+                .map(|row| row.chars().count())
+                .max()
+                .unwrap_or(0) as u16,
+            height: rows.len() as u16,
+            snake: Snake {
+                head,
+                tail,
+                direction,
+            },
+            items,
+            walls,
+        })
+    }
+}
+
+impl fmt::Display for BoardError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BoardError::MissingDirection => {
+                write!(f, "the first line has to be e.g. 'direction: up'")
+            }
+            BoardError::UnknownDirection(direction) => {
+                write!(f, "unknown direction '{}'", direction)
+            }
+            BoardError::Empty => write!(f, "the board has no rows"),
+            BoardError::UnknownCell { cell, row, column } => write!(
+                f,
+                "unknown cell '{}' in row {}, column {}",
+                cell, row, column
+            ),
+            BoardError::MissingHead => write!(f, "the snake has no head 'H'"),
+            BoardError::MultipleHeads => write!(f, "the snake has more than one head 'H'"),
+            BoardError::AmbiguousTail => {
+                write!(f, "the parts of the tail can be connected in more than one order")
+            }
+            BoardError::DetachedTail(position) => write!(
+                f,
+                "the tail at {}, {} isn't connected to the snake",
+                position.x, position.y
+            ),
+        }
+    }
+}
+
+impl error::Error for BoardError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BOARD: &str = "
+        direction: left
+        .....
+        .H.*.
+        .o..$
+        .oo#.
+    ";
+
+    #[test]
+    fn test_parse_board() {
+        let board: Board = BOARD.parse().unwrap();
+
+        assert_eq!((board.width, board.height), (5, 4));
+        assert_eq!(board.snake.direction, Direction::Left);
+        assert_eq!(board.snake.head, Vec2::new(1, 1));
+        assert_eq!(
+            board.snake.tail,
+            vec![Vec2::new(1, 2), Vec2::new(1, 3), Vec2::new(2, 3)]
+        );
+        assert_eq!(board.walls, vec![Vec2::new(3, 3)]);
+        assert_eq!(
+            board
+                .items
+                .iter()
+                .map(|item| (item.kind, item.position))
+                .collect::<Vec<_>>(),
+            vec![
+                (ItemKind::Apple, Vec2::new(3, 1)),
+                (ItemKind::GoldenApple, Vec2::new(4, 2)),
+            ]
+        );
+
+        let printed = board.to_string();
+        assert_eq!(printed.parse::<Board>().unwrap(), board);
+        assert_eq!(
+            printed,
+            "direction: left\n.....\n.H.*.\n.o..$\n.oo#.\n"
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            ".H.".parse::<Board>(),
+            Err(BoardError::MissingDirection)
+        );
+        assert_eq!(
+            "direction: up\n.H.\n.oA".parse::<Board>(),
+            Err(BoardError::UnknownCell {
+                cell: 'A',
+                row: 2,
+                column: 3
+            })
+        );
+        assert_eq!(
+            "direction: up\n.H.\n.oo\n.oo".parse::<Board>(),
+            Err(BoardError::AmbiguousTail)
+        );
+        assert_eq!(
+            "direction: up\n.H.\n.o.\n...\no..".parse::<Board>(),
+            Err(BoardError::DetachedTail(Vec2::new(0, 3)))
+        );
+    }
+}
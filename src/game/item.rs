@@ -0,0 +1,31 @@
+use crate::item::ItemKind;
+use serde::{Deserialize, Serialize};
+use vek::Vec2;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Item {
+    pub position: Vec2<i32>,
+    pub kind: ItemKind,
+    /// Ticks until the item disappears, if it is timed
+    pub remaining_ticks: Option<u32>,
+}
+
+impl Item {
+    pub fn new(kind: ItemKind, position: Vec2<i32>, lifetime: u32) -> Item {
+        Item {
+            position,
+            kind,
+            remaining_ticks: if kind.has_effect() || lifetime == 0 {
+                None
+            } else {
+                Some(lifetime)
+            },
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ActiveEffect {
+    pub kind: ItemKind,
+    pub remaining_ticks: u32,
+}
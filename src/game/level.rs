@@ -0,0 +1,195 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::{error, fmt, fs, io};
+use vek::Vec2;
+
+/// Board with walls, loaded from a text file in which every line is a row of
+/// cells: `#` is a wall, `.` or a space is empty and `S` is the start of the
+/// snake's head.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Level {
+    pub name: String,
+    pub width: u16,
+    pub height: u16,
+    pub walls: Vec<Vec2<i32>>,
+    pub start: Option<Vec2<i32>>,
+}
+
+#[derive(Debug)]
+pub enum LevelError {
+    Io(io::Error),
+    Empty,
+    UnknownCell {
+        cell: char,
+        row: usize,
+        column: usize,
+    },
+    MultipleStarts,
+}
+
+impl Level {
+    pub fn parse(name: &str, content: &str) -> Result<Level, LevelError> {
+        let rows: Vec<&str> = content
+            .lines()
+            .map(|line| line.trim_end_matches('\r'))
+            .collect();
+        let rows = match rows.iter().rposition(|row| !row.trim().is_empty()) {
+            Some(last) => &rows[..=last],
+            None => return Err(LevelError::Empty),
+        };
+
+        let width = rows
+            .iter()
+            .map(|row| row.chars().count())
+            .max()
+            .unwrap_or(0);
+        let mut level = Level {
+            name: name.to_string(),
+            width: width as u16,
+            height: rows.len() as u16,
+            walls: Vec::new(),
+            start: None,
+        };
+
+        // Shorter rows are filled up with empty cells, as editors may trim
+        // trailing spaces
+        for (y, row) in rows.iter().enumerate() {
+            for (x, cell) in row.chars().enumerate() {
+                let position = Vec2::new(x as i32, y as i32);
+                match cell {
+                    '#' => level.walls.push(position),
+                    '.' | ' ' => {}
+                    'S' if level.start.is_none() => level.start = Some(position),
+                    'S' => return Err(LevelError::MultipleStarts),
+                    _ => {
+                        return Err(LevelError::UnknownCell {
+                            cell,
+                            row: y + 1,
+                            column: x + 1,
+                        })
+                    }
+                }
+            }
+        }
+
+        Ok(level)
+    }
+
+    /// Loads a level, which is named after its file
+    pub fn load(path: &Path) -> Result<Level, LevelError> {
+        let content = fs::read_to_string(path).map_err(LevelError::Io)?;
+        let name = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        Level::parse(&name, &content)
+    }
+
+    /// Loads the levels in the directory, which are `.txt` files, sorted by
+    /// name. Levels that can't be loaded are skipped.
+    pub fn load_dir(dir: &Path) -> Vec<Level> {
+        let entries = match fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Vec::new(),
+            Err(err) => {
+                eprintln!("Unable to list the levels in '{}': {}", dir.display(), err);
+                return Vec::new();
+            }
+        };
+
+        let mut levels: Vec<Level> = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|extension| extension == "txt"))
+            .filter_map(|path| match Level::load(&path) {
+                Ok(level) => Some(level),
+                Err(err) => {
+                    eprintln!("Unable to load level '{}': {}", path.display(), err);
+                    None
+                }
+            })
+            .collect();
+        levels.sort_by(|a, b| a.name.cmp(&b.name));
+
+        levels
+    }
+}
+
+impl fmt::Display for LevelError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LevelError::Io(err) => write!(f, "{}", err),
+            LevelError::Empty => write!(f, "the level is empty"),
+            LevelError::UnknownCell { cell, row, column } => {
+                write!(
+                    f,
+                    "unknown cell '{}' in row {}, column {}",
+                    cell, row, column
+                )
+            }
+            LevelError::MultipleStarts => write!(f, "the level has more than one start"),
+        }
+    }
+}
+
+impl error::Error for LevelError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let level = Level::parse("corner", "###\n#S\n#..\n").unwrap();
+
+        assert_eq!((level.width, level.height), (3, 3));
+        assert_eq!(level.start, Some(Vec2::new(1, 1)));
+        assert_eq!(
+            level.walls,
+            vec![
+                Vec2::new(0, 0),
+                Vec2::new(1, 0),
+                Vec2::new(2, 0),
+                Vec2::new(0, 1),
+                Vec2::new(0, 2),
+            ]
+        );
+
+        assert!(matches!(
+            Level::parse("empty", "\n\n"),
+            Err(LevelError::Empty)
+        ));
+        assert!(matches!(
+            Level::parse("two", "S.S"),
+            Err(LevelError::MultipleStarts)
+        ));
+        assert!(matches!(
+            Level::parse("apple", "..*"),
+            Err(LevelError::UnknownCell {
+                cell: '*',
+                row: 1,
+                column: 3
+            })
+        ));
+    }
+
+    #[test]
+    fn test_load_dir() {
+        let dir = std::env::temp_dir().join(format!("snake-levels-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("maze.txt"), "#.#\n.S.").unwrap();
+        fs::write(dir.join("arena.txt"), "....\n.S..").unwrap();
+        fs::write(dir.join("broken.txt"), "S.S").unwrap();
+        fs::write(dir.join("notes.md"), "# Levels").unwrap();
+
+        let names: Vec<String> = Level::load_dir(&dir)
+            .into_iter()
+            .map(|level| level.name)
+            .collect();
+        assert_eq!(names, vec!["arena", "maze"]);
+        assert!(Level::load_dir(&dir.join("missing")).is_empty());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
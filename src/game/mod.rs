@@ -0,0 +1,780 @@
+//! Rules of the game that are shared by all frontends
+
+use crate::config;
+use crate::game_mode::{EndReason, GameMode, GameProgress};
+use crate::item::ItemKind;
+use crate::settings::Settings;
+use crate::statistics::{Death, DeathCause};
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::SeedableRng;
+use rand_pcg::Pcg32;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use vek::Vec2;
+
+pub use board::{Board, BoardError};
+pub use direction::Direction;
+pub use item::{ActiveEffect, Item};
+pub use level::{Level, LevelError};
+pub use occupancy::Occupancy;
+pub use replay::{Replay, ReplayError};
+pub use session::GameSession;
+pub use snake::Snake;
+
+mod board;
+mod direction;
+mod item;
+mod level;
+mod occupancy;
+mod replay;
+mod session;
+mod snake;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum GameEvent {
+    Turned {
+        from: Direction,
+        to: Direction,
+    },
+    ItemEaten {
+        kind: ItemKind,
+        tick: u64,
+        position: Vec2<i32>,
+        /// Where the item that replaces the eaten one has been spawned, if
+        /// there was space for it
+        new_position: Option<Vec2<i32>>,
+    },
+    /// An item appeared on the board, replacing an eaten or expired one
+    ItemSpawned {
+        kind: ItemKind,
+        position: Vec2<i32>,
+    },
+    /// The snake moved forward by one tile
+    Moved,
+    Ended {
+        reason: EndReason,
+        death: Option<Death>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Game {
+    pub mode: GameMode,
+    pub settings: Settings,
+    pub snake: Snake,
+    direction_queue: Vec<Direction>,
+    pub apples: Vec<Item>,
+    pub effects: Vec<ActiveEffect>,
+    /// Cells taken by the snake, the apples and the walls, kept in sync with
+    /// all of them
+    occupancy: Occupancy,
+
+    /// Speed of the snake in tiles per second
+    pub speed: f64,
+    tick_progress: f64,
+    elapsed_updates: u64,
+    pub ticks: u64,
+    pub apples_eaten: u32,
+    pub score: u32,
+    /// Why the game ended, if it did
+    pub end: Option<EndReason>,
+    /// Seed the random number generator started with, so that the game can
+    /// be replayed
+    pub seed: u64,
+    /// Part of the game state, so that a resumed game continues with the
+    /// same items it would have spawned otherwise
+    rng: Pcg32,
+}
+
+impl Game {
+    pub fn new(mode: GameMode, settings: Settings) -> Game {
+        // The snake and the board are set up by `reset`
+        let mut game = Game {
+            mode,
+            snake: Snake::new(settings.start_position()),
+            occupancy: Occupancy::new(settings.board_width, settings.board_height),
+            speed: settings.speed,
+            settings,
+            direction_queue: Vec::new(),
+            apples: Vec::new(),
+            effects: Vec::new(),
+
+            tick_progress: 0.0,
+            elapsed_updates: 0,
+            ticks: 0,
+            apples_eaten: 0,
+            score: 0,
+            end: None,
+            seed: 0,
+            rng: Pcg32::seed_from_u64(0),
+        };
+
+        game.reset();
+
+        game
+    }
+
+    /// Starts the game over, with a new random seed unless the settings fix
+    /// one
+    pub fn reset(&mut self) {
+        self.seed = self.settings.seed.unwrap_or_else(rand::random);
+        self.rng = Pcg32::seed_from_u64(self.seed);
+
+        self.occupancy = Occupancy::new(self.settings.board_width, self.settings.board_height);
+        for wall in self.settings.walls() {
+            self.occupancy.add_wall(wall);
+        }
+        self.snake = Snake::new(self.settings.start_position());
+        self.occupancy.add_snake(&self.snake.head);
+        for _ in 0..self.settings.snake_start_size {
+            self.grow_snake();
+        }
+        self.score = self.settings.snake_start_size;
+
+        self.apples.clear();
+        for _ in 0..self.settings.apple_count {
+            if self.spawn_apple(ItemKind::Apple).is_none() {
+                break;
+            }
+        }
+
+        self.direction_queue.clear();
+        self.tick_progress = 0.0;
+        self.elapsed_updates = 0;
+        self.ticks = 0;
+        self.apples_eaten = 0;
+        self.effects.clear();
+        self.end = None;
+        self.update_speed();
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.end.is_some()
+    }
+
+    /// Sets up a game on the given board, whose walls take the place of the
+    /// level in the settings
+    pub fn from_board(mode: GameMode, settings: Settings, board: &Board) -> Game {
+        let mut settings = Settings {
+            apple_count: board.items.len() as u32,
+            snake_start_size: 0,
+            ..settings
+        };
+        settings.use_level(Level {
+            name: "board".to_string(),
+            width: board.width,
+            height: board.height,
+            walls: board.walls.clone(),
+            start: Some(board.snake.head),
+        });
+        let mut game = Game::new(mode, settings);
+
+        for index in (0..game.apples.len()).rev() {
+            game.remove_item(index);
+        }
+        for item in board.items.iter() {
+            let item = game.create_item(item.kind, item.position);
+            game.add_item(item);
+        }
+        for position in board.snake.tail.iter() {
+            game.occupancy.add_snake(position);
+        }
+        game.snake = board.snake.clone();
+        game.score = game.snake.tail.len() as u32;
+
+        game
+    }
+
+    pub fn board(&self) -> Board {
+        Board::of(self)
+    }
+
+    /// Cells taken by the snake, the apples and the walls
+    pub fn occupancy(&self) -> &Occupancy {
+        &self.occupancy
+    }
+
+    /// Amount of updates the game has made so far
+    pub fn updates(&self) -> u64 {
+        self.elapsed_updates
+    }
+
+    /// Queues a direction change that is applied on one of the next ticks
+    pub fn queue_direction(&mut self, direction: Direction) {
+        self.direction_queue.push(direction);
+    }
+
+    pub fn spawn_apple(&mut self, kind: ItemKind) -> Option<&Item> {
+        let position = self.choose_apple_position()?;
+        let apple = self.create_item(kind, position);
+        self.add_item(apple);
+
+        self.apples.last()
+    }
+
+    fn add_item(&mut self, item: Item) {
+        self.occupancy.add_item(&item.position);
+        self.apples.push(item);
+    }
+
+    fn replace_item(&mut self, index: usize, item: Item) {
+        self.occupancy.remove_item(&self.apples[index].position);
+        self.occupancy.add_item(&item.position);
+        self.apples[index] = item;
+    }
+
+    fn remove_item(&mut self, index: usize) {
+        let item = self.apples.remove(index);
+        self.occupancy.remove_item(&item.position);
+    }
+
+    fn grow_snake(&mut self) {
+        self.snake.grow_tail();
+        if let Some(position) = self.snake.tail.last() {
+            self.occupancy.add_snake(position);
+        }
+    }
+
+    fn shrink_snake(&mut self, amount: usize) {
+        let new_len = self.snake.tail.len().saturating_sub(amount);
+        for position in self.snake.tail[new_len..].iter() {
+            self.occupancy.remove_snake(position);
+        }
+        self.snake.shrink_tail(amount);
+    }
+
+    fn move_snake(&mut self) {
+        // Only the end of the tail is left and the new head is entered
+        let last_position = *self.snake.tail.last().unwrap_or(&self.snake.head);
+        self.occupancy.remove_snake(&last_position);
+        self.snake.move_forward();
+        self.occupancy.add_snake(&self.snake.head);
+    }
+
+    fn create_item(&self, kind: ItemKind, position: Vec2<i32>) -> Item {
+        Item::new(kind, position, self.settings.item_durations.get(kind))
+    }
+
+    pub fn choose_item_kind(&mut self) -> ItemKind {
+        let weights = ItemKind::ALL
+            .iter()
+            .map(|kind| self.settings.item_weights.get(*kind));
+
+        match WeightedIndex::new(weights) {
+            Ok(distribution) => ItemKind::ALL[distribution.sample(&mut self.rng)],
+            Err(_) => ItemKind::Apple,
+        }
+    }
+
+    /// Chooses a random free cell that the snake does not enter next
+    pub fn choose_apple_position(&mut self) -> Option<Vec2<i32>> {
+        let next_head_position = self.snake.get_next_head_position();
+
+        self.occupancy
+            .choose_free_cell(&mut self.rng, &next_head_position)
+    }
+
+    fn position_is_out_of_bounds(&self, position: &Vec2<i32>) -> bool {
+        position.x < 0
+            || position.x >= self.settings.board_width as i32
+            || position.y < 0
+            || position.y >= self.settings.board_height as i32
+    }
+
+    pub fn effect_is_active(&self, kind: ItemKind) -> bool {
+        self.effects.iter().any(|effect| effect.kind == kind)
+    }
+
+    fn update_speed(&mut self) {
+        self.speed = self
+            .effects
+            .iter()
+            .fold(self.settings.speed, |speed, effect| {
+                speed * effect.kind.speed_factor()
+            });
+    }
+
+    fn eat(&mut self, kind: ItemKind) {
+        match kind {
+            ItemKind::Apple => {
+                self.grow_snake();
+                self.score += 1;
+                self.apples_eaten += 1;
+            }
+            ItemKind::GoldenApple => {
+                self.grow_snake();
+                self.score += 1 + config::GOLDEN_APPLE_BONUS;
+                self.apples_eaten += 1;
+            }
+            ItemKind::Poison => {
+                self.shrink_snake(config::POISON_PENALTY as usize);
+                self.score = self.score.saturating_sub(config::POISON_PENALTY);
+            }
+            ItemKind::Shrink => {
+                let amount = self.snake.tail.len() / 2;
+                self.shrink_snake(amount);
+            }
+            ItemKind::SpeedUp | ItemKind::SlowDown | ItemKind::Ghost => {
+                let remaining_ticks = self.settings.item_durations.get(kind);
+
+                self.effects.retain(|effect| effect.kind != kind);
+                if remaining_ticks > 0 {
+                    self.effects.push(ActiveEffect {
+                        kind,
+                        remaining_ticks,
+                    });
+                }
+                self.update_speed();
+            }
+        }
+    }
+
+    /// Counts down timed items and effects and removes the expired ones.
+    fn age_items_and_effects(&mut self, events: &mut Vec<GameEvent>) {
+        for effect in self.effects.iter_mut() {
+            effect.remaining_ticks = effect.remaining_ticks.saturating_sub(1);
+        }
+        let effect_count = self.effects.len();
+        self.effects.retain(|effect| effect.remaining_ticks > 0);
+        if self.effects.len() != effect_count {
+            self.update_speed();
+        }
+
+        let mut index = 0;
+        while index < self.apples.len() {
+            let remaining_ticks = &mut self.apples[index].remaining_ticks;
+            if let Some(ticks) = remaining_ticks {
+                *ticks = ticks.saturating_sub(1);
+            }
+
+            if *remaining_ticks != Some(0) {
+                index += 1;
+                continue;
+            }
+
+            // Expired items are replaced by plain apples to keep the apple count
+            match self.choose_apple_position() {
+                Some(position) => {
+                    let apple = self.create_item(ItemKind::Apple, position);
+                    self.replace_item(index, apple);
+                    events.push(GameEvent::ItemSpawned {
+                        kind: ItemKind::Apple,
+                        position,
+                    });
+                    index += 1;
+                }
+                None => {
+                    self.remove_item(index);
+                }
+            }
+        }
+    }
+
+    pub fn progress(&self) -> GameProgress {
+        GameProgress {
+            ticks: self.ticks,
+            elapsed_seconds: self.elapsed_updates as f64 / config::UPDATES_PER_SECOND,
+            length: self.snake.tail.len() as u32 + 1,
+            score: self.score,
+        }
+    }
+
+    /// Whether the snake has filled the board, so that no apple is left and
+    /// no cell is free
+    pub fn board_is_full(&self) -> bool {
+        self.apples.is_empty() && self.occupancy.free_cells().is_empty()
+    }
+
+    /// Tops the apples back up to the apple count once cells are free again,
+    /// e.g. after poison, since apples that found no free cell were dropped
+    fn spawn_missing_apples(&mut self, events: &mut Vec<GameEvent>) {
+        while self.apples.len() < self.settings.apple_count as usize {
+            let position = match self.spawn_apple(ItemKind::Apple) {
+                Some(apple) => apple.position,
+                None => return,
+            };
+
+            events.push(GameEvent::ItemSpawned {
+                kind: ItemKind::Apple,
+                position,
+            });
+        }
+    }
+
+    fn end(&mut self, reason: EndReason, death: Option<Death>, events: &mut Vec<GameEvent>) {
+        self.end = Some(reason);
+        events.push(GameEvent::Ended { reason, death });
+    }
+
+    fn check_mode_end(&mut self, events: &mut Vec<GameEvent>) {
+        if self.is_over() {
+            return;
+        }
+
+        if let Some(reason) = self.mode.check_end(&self.progress()) {
+            self.end(reason, None, events);
+        }
+    }
+
+    /// Advances the game by one update, which is called
+    /// `config::UPDATES_PER_SECOND` times per second by the frontends
+    pub fn update(&mut self) -> Vec<GameEvent> {
+        let mut events = Vec::new();
+
+        if self.is_over() {
+            return events;
+        }
+
+        self.elapsed_updates += 1;
+        self.check_mode_end(&mut events);
+
+        self.tick_progress += self.speed / config::UPDATES_PER_SECOND;
+        while self.tick_progress >= 1.0 && !self.is_over() {
+            self.tick_progress -= 1.0;
+            self.tick(&mut events);
+            self.check_mode_end(&mut events);
+        }
+
+        events
+    }
+
+    fn tick(&mut self, events: &mut Vec<GameEvent>) {
+        if !self.direction_queue.is_empty() {
+            let dir_match = self
+                .direction_queue
+                .iter()
+                .enumerate()
+                .rfind(|(_index, &dir)| {
+                    dir != self.snake.direction && dir != self.snake.direction.opposite()
+                });
+
+            if let Some((index, &dir)) = dir_match {
+                events.push(GameEvent::Turned {
+                    from: self.snake.direction,
+                    to: dir,
+                });
+
+                self.snake.direction = dir;
+                self.direction_queue = Vec::from(&self.direction_queue[(index + 1)..]);
+            } else {
+                self.direction_queue.clear();
+            }
+        }
+
+        let next_head_pos = self.snake.get_next_head_position();
+
+        let collided_apple_index = self
+            .apples
+            .iter()
+            .position(|apple| apple.position == next_head_pos);
+
+        if let Some(index) = collided_apple_index {
+            let eaten_kind = self.apples[index].kind;
+            let new_position = self.choose_apple_position();
+
+            let mut spawned = None;
+            if let Some(new_position) = new_position {
+                let kind = self.choose_item_kind();
+                let item = self.create_item(kind, new_position);
+                self.replace_item(index, item);
+                spawned = Some(GameEvent::ItemSpawned {
+                    kind,
+                    position: new_position,
+                });
+            } else {
+                self.remove_item(index);
+            }
+
+            self.eat(eaten_kind);
+            events.push(GameEvent::ItemEaten {
+                kind: eaten_kind,
+                tick: self.ticks,
+                position: next_head_pos,
+                new_position,
+            });
+            events.extend(spawned);
+        }
+
+        let is_ghost = self.effect_is_active(ItemKind::Ghost);
+        let death_cause = if self.position_is_out_of_bounds(&next_head_pos)
+            || self.occupancy.is_wall(&next_head_pos)
+        {
+            Some(DeathCause::Wall)
+        } else if !is_ghost && self.occupancy.is_on_snake(&next_head_pos) {
+            Some(DeathCause::Tail)
+        } else {
+            None
+        };
+
+        if let Some(cause) = death_cause {
+            let death = Death {
+                cause,
+                x: next_head_pos.x,
+                y: next_head_pos.y,
+            };
+            self.end(EndReason::Crashed, Some(death), events);
+            return;
+        }
+
+        self.ticks += 1;
+        self.move_snake();
+
+        self.age_items_and_effects(events);
+        self.spawn_missing_apples(events);
+        events.push(GameEvent::Moved);
+
+        if self.board_is_full() {
+            self.end(EndReason::Victory, None, events);
+        }
+    }
+}
+
+/// Shows the board, e.g. to make failing tests easier to follow
+impl fmt::Display for Game {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.board())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn game(apple_count: u32) -> Game {
+        let settings = Settings {
+            apple_count,
+            snake_start_size: 2,
+            ..Settings::default()
+        };
+
+        Game::new(GameMode::Endless, settings)
+    }
+
+    fn game_on(board: &str) -> Game {
+        let board = board.parse().unwrap_or_else(|err| panic!("invalid board: {}", err));
+
+        Game::from_board(GameMode::Endless, Settings::default(), &board)
+    }
+
+    fn run_ticks(game: &mut Game, ticks: u64) -> Vec<GameEvent> {
+        let mut events = Vec::new();
+        while game.ticks < ticks && !game.is_over() {
+            events.extend(game.update());
+        }
+        events
+    }
+
+    #[test]
+    fn test_snake_moves_and_turns() {
+        let mut game = game(0);
+        let head = game.snake.head;
+
+        run_ticks(&mut game, 1);
+        assert_eq!(game.snake.head, head - Vec2::new(0, 1));
+
+        game.queue_direction(Direction::Left);
+        let events = run_ticks(&mut game, 2);
+        assert!(events.contains(&GameEvent::Turned {
+            from: Direction::Up,
+            to: Direction::Left,
+        }));
+        assert_eq!(game.snake.head, head - Vec2::new(1, 1));
+        assert_eq!(game.snake.tail.len(), 2);
+    }
+
+    #[test]
+    fn test_eating_an_apple_grows_the_snake() {
+        let mut game = game(0);
+        let position = game.snake.get_next_head_position();
+        game.add_item(Item::new(ItemKind::Apple, position, 0));
+        assert!(!game.occupancy.is_free(&position));
+
+        let events = run_ticks(&mut game, 1);
+
+        let new_position = events.iter().find_map(|event| match event {
+            GameEvent::ItemEaten {
+                kind: ItemKind::Apple,
+                tick: 0,
+                position: eaten_position,
+                new_position: Some(new_position),
+            } if *eaten_position == position => Some(*new_position),
+            _ => None,
+        });
+        assert!(new_position.is_some_and(|new_position| events
+            .iter()
+            .any(|event| matches!(event, GameEvent::ItemSpawned { position, .. } if *position == new_position))));
+        assert_eq!(game.score, 3);
+        assert_eq!(game.snake.tail.len(), 3);
+    }
+
+    #[test]
+    fn test_occupancy_follows_snake_and_apples() {
+        let mut game = game(5);
+        game.queue_direction(Direction::Left);
+        run_ticks(&mut game, 4);
+        game.queue_direction(Direction::Down);
+        run_ticks(&mut game, 6);
+        game.eat(ItemKind::Shrink);
+
+        let mut expected = Occupancy::new(game.settings.board_width, game.settings.board_height);
+        expected.add_snake(&game.snake.head);
+        for position in game.snake.tail.iter() {
+            expected.add_snake(position);
+        }
+        for apple in game.apples.iter() {
+            expected.add_item(&apple.position);
+        }
+
+        let mut free_cells = game.occupancy.free_cells().to_vec();
+        let mut expected_free_cells = expected.free_cells().to_vec();
+        free_cells.sort_by_key(|position| (position.x, position.y));
+        expected_free_cells.sort_by_key(|position| (position.x, position.y));
+        assert_eq!(free_cells, expected_free_cells);
+    }
+
+    #[test]
+    fn test_crashing_into_the_wall_ends_the_game() {
+        let mut game = game(1);
+
+        let events = run_ticks(&mut game, u64::MAX);
+
+        assert_eq!(game.end, Some(EndReason::Crashed));
+        match events.last() {
+            Some(GameEvent::Ended {
+                reason: EndReason::Crashed,
+                death: Some(death),
+            }) => {
+                assert_eq!(death.cause, DeathCause::Wall);
+                assert_eq!(death.y, -1);
+            }
+            event => panic!("unexpected event {:?}", event),
+        }
+        assert!(game.update().is_empty());
+    }
+
+    #[test]
+    fn test_crashing_into_a_level_wall_ends_the_game() {
+        let mut settings = Settings::default();
+        settings.use_level(Level::parse("pillar", "......\n..#...\n......\n..S...").unwrap());
+        let mut game = Game::new(GameMode::Endless, settings);
+
+        let events = run_ticks(&mut game, u64::MAX);
+
+        assert_eq!(game.ticks, 1);
+        assert!(events.contains(&GameEvent::Ended {
+            reason: EndReason::Crashed,
+            death: Some(Death {
+                cause: DeathCause::Wall,
+                x: 2,
+                y: 1,
+            }),
+        }));
+    }
+
+    #[test]
+    fn test_seed_determines_the_apples() {
+        let settings = Settings {
+            apple_count: 3,
+            seed: Some(42),
+            ..Settings::default()
+        };
+        let mut game = Game::new(GameMode::Endless, settings.clone());
+        let apples = game.apples.clone();
+
+        assert_eq!(game.seed, 42);
+        assert_eq!(Game::new(GameMode::Endless, settings).apples, apples);
+        game.reset();
+        assert_eq!(game.apples, apples);
+    }
+
+    #[test]
+    fn test_turning_into_the_tail_ends_the_game() {
+        let mut game = game_on(
+            "
+            direction: left
+            ......
+            .Hoo..
+            .ooo..
+            .o....
+            ",
+        );
+        assert_eq!(game.snake.tail.len(), 6);
+
+        game.queue_direction(Direction::Down);
+        let events = run_ticks(&mut game, 1);
+        assert!(
+            events.contains(&GameEvent::Ended {
+                reason: EndReason::Crashed,
+                death: Some(Death {
+                    cause: DeathCause::Tail,
+                    x: 1,
+                    y: 2,
+                }),
+            }),
+            "{}",
+            game
+        );
+    }
+
+    #[test]
+    fn test_dropped_apples_come_back() {
+        let mut game = game_on(
+            "
+            direction: left
+            ~*Ho
+            oooo
+            ",
+        );
+
+        // Neither item finds a free cell when it is eaten
+        run_ticks(&mut game, 1);
+        assert_eq!(game.apples.len(), 1, "{}", game);
+
+        // Shrinking frees cells for both of them
+        let events = run_ticks(&mut game, 2);
+        assert_eq!(game.apples.len(), 2, "{}", game);
+        assert!(game
+            .apples
+            .iter()
+            .all(|apple| apple.kind == ItemKind::Apple));
+        assert_eq!(
+            events
+                .iter()
+                .filter(|event| matches!(event, GameEvent::ItemSpawned { .. }))
+                .count(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_free_cell_ahead_is_no_victory() {
+        let mut game = game_on(
+            "
+            direction: down
+            Ho
+            .o
+            oo
+            ",
+        );
+
+        // Only the cell that the tail has just left is free
+        run_ticks(&mut game, 1);
+        assert_eq!(game.occupancy.free_cells(), &[Vec2::new(0, 2)]);
+        assert!(!game.is_over(), "{}", game);
+
+        run_ticks(&mut game, 2);
+        assert!(!game.is_over(), "{}", game);
+    }
+
+    #[test]
+    fn test_board_of_game() {
+        let board = "direction: right\n.....\n.oH*.\n..#..\n";
+        let mut game = game_on(board);
+        assert_eq!(game.to_string(), board);
+
+        run_ticks(&mut game, 1);
+        assert_eq!(game.apples_eaten, 1);
+        assert_eq!(game.snake.head, Vec2::new(3, 1));
+        assert_eq!(game.snake.tail, vec![Vec2::new(2, 1), Vec2::new(1, 1)]);
+    }
+}
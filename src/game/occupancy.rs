@@ -0,0 +1,214 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use vek::Vec2;
+
+/// Tracks which cells of the board are taken by the snake, an item or a wall, so that
+/// collisions can be checked and free cells chosen without scanning the snake.
+///
+/// Positions outside of the board are ignored, e.g. the tail of a snake that
+/// starts longer than the board is high.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Occupancy {
+    width: i32,
+    height: i32,
+    /// Snake segments per cell, more than one while a ghost crosses its tail
+    snake: Vec<u16>,
+    items: Vec<bool>,
+    walls: Vec<bool>,
+    /// Cells without snake, item or wall in no particular order
+    free_cells: Vec<Vec2<i32>>,
+    /// Index of each cell in `free_cells`, if it is free
+    free_index: Vec<Option<usize>>,
+}
+
+impl Occupancy {
+    pub fn new(width: u16, height: u16) -> Occupancy {
+        let cell_count = width as usize * height as usize;
+
+        let mut free_cells = Vec::with_capacity(cell_count);
+        for y in 0..height as i32 {
+            for x in 0..width as i32 {
+                free_cells.push(Vec2::new(x, y));
+            }
+        }
+
+        Occupancy {
+            width: width as i32,
+            height: height as i32,
+            snake: vec![0; cell_count],
+            items: vec![false; cell_count],
+            walls: vec![false; cell_count],
+            free_cells,
+            free_index: (0..cell_count).map(Some).collect(),
+        }
+    }
+
+    fn index(&self, position: &Vec2<i32>) -> Option<usize> {
+        if position.x < 0 || position.x >= self.width || position.y < 0 || position.y >= self.height
+        {
+            return None;
+        }
+
+        Some((position.y * self.width + position.x) as usize)
+    }
+
+    pub fn is_on_snake(&self, position: &Vec2<i32>) -> bool {
+        self.index(position)
+            .is_some_and(|index| self.snake[index] > 0)
+    }
+
+    pub fn is_free(&self, position: &Vec2<i32>) -> bool {
+        self.index(position)
+            .is_some_and(|index| self.free_index[index].is_some())
+    }
+
+    pub fn is_wall(&self, position: &Vec2<i32>) -> bool {
+        self.index(position).is_some_and(|index| self.walls[index])
+    }
+
+    pub fn add_wall(&mut self, position: &Vec2<i32>) {
+        if let Some(index) = self.index(position) {
+            self.walls[index] = true;
+            self.update_free_cell(index);
+        }
+    }
+
+    pub fn add_snake(&mut self, position: &Vec2<i32>) {
+        if let Some(index) = self.index(position) {
+            self.snake[index] += 1;
+            self.update_free_cell(index);
+        }
+    }
+
+    pub fn remove_snake(&mut self, position: &Vec2<i32>) {
+        if let Some(index) = self.index(position) {
+            self.snake[index] = self.snake[index].saturating_sub(1);
+            self.update_free_cell(index);
+        }
+    }
+
+    pub fn add_item(&mut self, position: &Vec2<i32>) {
+        if let Some(index) = self.index(position) {
+            self.items[index] = true;
+            self.update_free_cell(index);
+        }
+    }
+
+    pub fn remove_item(&mut self, position: &Vec2<i32>) {
+        if let Some(index) = self.index(position) {
+            self.items[index] = false;
+            self.update_free_cell(index);
+        }
+    }
+
+    pub fn free_cells(&self) -> &[Vec2<i32>] {
+        &self.free_cells
+    }
+
+    /// Amount of free cells apart from the excluded one
+    pub fn free_cell_count_without(&self, excluded: &Vec2<i32>) -> usize {
+        if self.is_free(excluded) {
+            self.free_cells.len() - 1
+        } else {
+            self.free_cells.len()
+        }
+    }
+
+    /// Chooses a free cell apart from the excluded one, with every such cell
+    /// being equally likely
+    pub fn choose_free_cell<R: Rng>(&self, rng: &mut R, excluded: &Vec2<i32>) -> Option<Vec2<i32>> {
+        let excluded_index = self
+            .index(excluded)
+            .and_then(|index| self.free_index[index]);
+
+        let count = self.free_cell_count_without(excluded);
+        if count == 0 {
+            return None;
+        }
+
+        let mut index = rng.gen_range(0, count);
+        // The last free cell takes the place of the excluded one
+        if Some(index) == excluded_index {
+            index = self.free_cells.len() - 1;
+        }
+
+        Some(self.free_cells[index])
+    }
+
+    fn update_free_cell(&mut self, index: usize) {
+        let is_free = self.snake[index] == 0 && !self.items[index] && !self.walls[index];
+
+        match (is_free, self.free_index[index]) {
+            (true, None) => {
+                let position = Vec2::new(index as i32 % self.width, index as i32 / self.width);
+                self.free_index[index] = Some(self.free_cells.len());
+                self.free_cells.push(position);
+            }
+            (false, Some(free_index)) => {
+                self.free_cells.swap_remove(free_index);
+                self.free_index[index] = None;
+
+                if let Some(moved) = self.free_cells.get(free_index).cloned() {
+                    if let Some(moved_index) = self.index(&moved) {
+                        self.free_index[moved_index] = Some(free_index);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_pcg::Pcg32;
+
+    #[test]
+    fn test_snake_items_and_walls_take_cells() {
+        let mut occupancy = Occupancy::new(3, 2);
+        let position = Vec2::new(1, 1);
+
+        occupancy.add_snake(&position);
+        occupancy.add_snake(&position);
+        occupancy.add_item(&Vec2::new(0, 0));
+        assert!(occupancy.is_on_snake(&position));
+        assert_eq!(occupancy.free_cells().len(), 4);
+
+        occupancy.remove_snake(&position);
+        assert!(occupancy.is_on_snake(&position));
+        occupancy.remove_snake(&position);
+        assert!(!occupancy.is_on_snake(&position));
+        assert!(occupancy.is_free(&position));
+        assert_eq!(occupancy.free_cells().len(), 5);
+
+        occupancy.add_snake(&Vec2::new(-1, 5));
+        assert!(!occupancy.is_on_snake(&Vec2::new(-1, 5)));
+        assert_eq!(occupancy.free_cells().len(), 5);
+
+        occupancy.add_wall(&Vec2::new(2, 0));
+        assert!(occupancy.is_wall(&Vec2::new(2, 0)));
+        assert!(!occupancy.is_free(&Vec2::new(2, 0)));
+        assert_eq!(occupancy.free_cells().len(), 4);
+    }
+
+    #[test]
+    fn test_choose_free_cell_skips_excluded_cell() {
+        let mut occupancy = Occupancy::new(2, 2);
+        occupancy.add_snake(&Vec2::new(0, 0));
+        occupancy.add_item(&Vec2::new(1, 0));
+        let excluded = Vec2::new(0, 1);
+
+        let mut rng = Pcg32::seed_from_u64(1);
+        for _ in 0..20 {
+            assert_eq!(
+                occupancy.choose_free_cell(&mut rng, &excluded),
+                Some(Vec2::new(1, 1))
+            );
+        }
+
+        occupancy.add_item(&Vec2::new(1, 1));
+        assert_eq!(occupancy.choose_free_cell(&mut rng, &excluded), None);
+    }
+}
@@ -0,0 +1,178 @@
+use super::{Direction, Game};
+use crate::game_mode::GameMode;
+use crate::settings::Settings;
+use crate::storage;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::{error, fmt, fs, io};
+
+/// Version of the replay file format, increased whenever the rules change in
+/// a way that would play recorded inputs differently
+pub const VERSION: u32 = 1;
+
+/// Replay of the last finished game in the data directory
+pub const LAST_REPLAY_FILE: &str = "last-replay.json";
+
+/// Recording of a game, consisting of its settings with the seed it was
+/// played with and the direction inputs of the player
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Replay {
+    pub version: u32,
+    pub mode: GameMode,
+    pub settings: Settings,
+    pub inputs: Vec<ReplayInput>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct ReplayInput {
+    /// Amount of updates the game had made when the input was queued
+    pub update: u64,
+    pub direction: Direction,
+}
+
+#[derive(Debug)]
+pub enum ReplayError {
+    Io(io::Error),
+    Invalid(serde_json::Error),
+    UnsupportedVersion(Option<u64>),
+}
+
+impl Replay {
+    /// Starts recording the given game, which has not been updated yet
+    pub fn new(game: &Game) -> Replay {
+        Replay {
+            version: VERSION,
+            mode: game.mode,
+            settings: Settings {
+                seed: Some(game.seed),
+                ..game.settings.clone()
+            },
+            inputs: Vec::new(),
+        }
+    }
+
+    pub fn record(&mut self, game: &Game, direction: Direction) {
+        self.inputs.push(ReplayInput {
+            update: game.updates(),
+            direction,
+        });
+    }
+
+    /// Forgets the inputs that were made after the given amount of updates,
+    /// e.g. when the game has been rewound
+    pub fn truncate(&mut self, updates: u64) {
+        self.inputs.retain(|input| input.update <= updates);
+    }
+
+    /// Creates the game at the start of the replay
+    pub fn start(&self) -> Game {
+        Game::new(self.mode, self.settings.clone())
+    }
+
+    /// Queues the inputs that were made before the next update of the game
+    pub fn queue_inputs(&self, game: &mut Game) {
+        let updates = game.updates();
+        let first = self.inputs.partition_point(|input| input.update < updates);
+
+        for input in self.inputs[first..]
+            .iter()
+            .take_while(|input| input.update == updates)
+        {
+            game.queue_direction(input.direction);
+        }
+    }
+
+    /// Plays the whole replay without a window and returns the finished game
+    pub fn play(&self) -> Game {
+        let mut game = self.start();
+
+        // Without inputs the snake keeps going straight, so every game
+        // eventually ends
+        while !game.is_over() {
+            self.queue_inputs(&mut game);
+            game.update();
+        }
+
+        game
+    }
+
+    pub fn from_json(content: &str) -> Result<Replay, ReplayError> {
+        let value: serde_json::Value =
+            serde_json::from_str(content).map_err(ReplayError::Invalid)?;
+        let version = value.get("version").and_then(|version| version.as_u64());
+        if version != Some(VERSION as u64) {
+            return Err(ReplayError::UnsupportedVersion(version));
+        }
+
+        serde_json::from_value(value).map_err(ReplayError::Invalid)
+    }
+
+    pub fn load(path: &Path) -> Result<Replay, ReplayError> {
+        let content = fs::read_to_string(path).map_err(ReplayError::Io)?;
+
+        Replay::from_json(&content)
+    }
+
+    /// Keeps the replay as the one of the last finished game
+    pub fn save_last(&self, dir: &Path) {
+        if let Err(err) = storage::save(dir, LAST_REPLAY_FILE, self) {
+            eprintln!("Unable to save the replay: {}", err);
+        }
+    }
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReplayError::Io(err) => write!(f, "{}", err),
+            ReplayError::Invalid(err) => write!(f, "invalid replay: {}", err),
+            ReplayError::UnsupportedVersion(Some(version)) => write!(
+                f,
+                "replay version {} is not supported, expected {}",
+                version, VERSION
+            ),
+            ReplayError::UnsupportedVersion(None) => write!(f, "replay has no version"),
+        }
+    }
+}
+
+impl error::Error for ReplayError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_replay_plays_the_same_game() {
+        let settings = Settings {
+            apple_count: 3,
+            ..Settings::default()
+        };
+        let mut game = Game::new(GameMode::Endless, settings);
+        let mut replay = Replay::new(&game);
+
+        let turns = [
+            (30, Direction::Left),
+            (75, Direction::Down),
+            (90, Direction::Right),
+            (140, Direction::Up),
+        ];
+        while !game.is_over() {
+            for (update, direction) in turns.iter() {
+                if *update == game.updates() {
+                    game.queue_direction(*direction);
+                    replay.record(&game, *direction);
+                }
+            }
+            game.update();
+        }
+
+        let json = serde_json::to_string(&replay).unwrap();
+        let replayed = Replay::from_json(&json).unwrap().play();
+
+        assert_eq!(replayed.snake, game.snake);
+        assert_eq!(replayed.apples, game.apples);
+        assert_eq!(replayed.score, game.score);
+        assert_eq!(replayed.ticks, game.ticks);
+    }
+}
@@ -0,0 +1,597 @@
+use super::{Direction, Game, GameEvent, Replay};
+use crate::achievements::{self, Achievement, AchievementEvent, AchievementTracker, Achievements};
+use crate::config;
+use crate::daily::{self, DailyChallenge, DailyResult, DailyResults};
+use crate::game_mode::{EndReason, GameMode, GameSummary};
+use crate::high_scores::HighScores;
+use crate::i18n;
+use crate::item::ItemKind;
+use crate::settings::Settings;
+use crate::statistics::{Death, GameRecorder, Statistics};
+use crate::storage;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::path::PathBuf;
+
+/// Outcome of a single update of a session
+#[derive(Default)]
+pub struct SessionUpdate {
+    pub events: Vec<GameEvent>,
+    /// Achievements that have been unlocked for the first time
+    pub unlocked: Vec<Achievement>,
+}
+
+/// A game together with the player's profile, so that high scores,
+/// achievements and statistics are recorded the same way in every frontend.
+///
+/// Only the game and its tracking state are serialized, the profile is
+/// loaded again when a saved session is restored.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct GameSession {
+    pub game: Game,
+    /// Summary of the last game, once it has ended
+    #[serde(skip)]
+    pub summary: Option<GameSummary>,
+    /// Directory in which the profile is stored
+    #[serde(skip, default = "storage::data_dir")]
+    data_dir: PathBuf,
+    #[serde(skip, default = "load_high_scores")]
+    high_scores: HighScores,
+    /// Challenge of the day that games in the daily mode are played for
+    #[serde(default)]
+    daily: Option<DailyChallenge>,
+    #[serde(skip, default = "load_daily_results")]
+    daily_results: DailyResults,
+    /// Whether the current game is the attempt at the daily challenge that
+    /// counts, which is decided as soon as it starts
+    #[serde(default)]
+    daily_attempt: bool,
+    #[serde(skip, default = "achievements::player_name")]
+    player: String,
+    #[serde(skip, default = "load_achievements")]
+    achievements: Achievements,
+    achievement_tracker: AchievementTracker,
+    recorder: GameRecorder,
+    /// Inputs of the game so far, saved as the last replay when it ends
+    replay: Replay,
+    /// Replay that is played back instead of taking inputs, which is not
+    /// recorded in the profile
+    #[serde(skip)]
+    playback: Option<Replay>,
+    /// States before the most recent ticks, if the mode allows rewinding
+    #[serde(skip)]
+    history: VecDeque<(Game, GameRecorder)>,
+    /// Crash that can still be rewound, which is recorded once the player
+    /// moves on to another game
+    #[serde(skip)]
+    pending_end: Option<(EndReason, Option<Death>)>,
+}
+
+impl GameSession {
+    pub fn new(mode: GameMode, settings: Settings) -> GameSession {
+        GameSession::with_data_dir(mode, settings, storage::data_dir())
+    }
+
+    /// Starts a session whose profile is stored in the directory
+    pub fn with_data_dir(mode: GameMode, settings: Settings, data_dir: PathBuf) -> GameSession {
+        let game = Game::new(mode, settings.clone());
+
+        GameSession {
+            recorder: GameRecorder::new(mode, settings),
+            replay: Replay::new(&game),
+            playback: None,
+            game,
+            summary: None,
+            high_scores: HighScores::load(&data_dir),
+            daily: None,
+            daily_results: DailyResults::load(&data_dir),
+            daily_attempt: false,
+            player: achievements::player_name(),
+            achievements: Achievements::load(&data_dir),
+            achievement_tracker: AchievementTracker::new(),
+            history: VecDeque::new(),
+            pending_end: None,
+            data_dir,
+        }
+    }
+
+    /// Plays the replay back from its start
+    pub fn from_replay(replay: Replay) -> GameSession {
+        let mut session = GameSession::new(replay.mode, replay.settings.clone());
+        session.game = replay.start();
+        session.replay = replay.clone();
+        session.playback = Some(replay);
+
+        session
+    }
+
+    pub fn is_playback(&self) -> bool {
+        self.playback.is_some()
+    }
+
+    pub fn restart(&mut self) {
+        self.finish_pending();
+        self.abandon_daily_attempt();
+
+        self.game.reset();
+        self.summary = None;
+        self.achievement_tracker = AchievementTracker::new();
+        self.recorder = GameRecorder::new(self.game.mode, self.game.settings.clone());
+        self.replay = Replay::new(&self.game);
+        self.history.clear();
+    }
+
+    pub fn daily(&self) -> Option<&DailyChallenge> {
+        self.daily.as_ref()
+    }
+
+    /// Plays games in the daily mode for the challenge, or for none
+    pub fn use_daily(&mut self, challenge: Option<DailyChallenge>) {
+        self.daily = challenge;
+    }
+
+    /// Queues a direction change of the player, which is ignored while a
+    /// replay is played back
+    pub fn queue_direction(&mut self, direction: Direction) {
+        if self.is_playback() {
+            return;
+        }
+
+        self.replay.record(&self.game, direction);
+        self.game.queue_direction(direction);
+    }
+
+    /// Whether the mode allows rewinding and the game hasn't been recorded
+    /// as finished yet, which leaves crashes in practice open to a rewind
+    pub fn can_rewind(&self) -> bool {
+        self.game.mode.allows_rewind() && (!self.game.is_over() || self.pending_end.is_some())
+    }
+
+    /// Restores the state before the last tick. Returns whether there was a
+    /// state to restore.
+    pub fn rewind(&mut self) -> bool {
+        if !self.can_rewind() {
+            return false;
+        }
+
+        match self.history.pop_back() {
+            Some((game, recorder)) => {
+                self.game = game;
+                self.recorder.rewind_to(recorder);
+                self.replay.truncate(self.game.updates());
+                self.pending_end = None;
+                self.summary = None;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Records the crash that hasn't been rewound, and returns the
+    /// achievements that it unlocks
+    pub fn finish_pending(&mut self) -> Vec<Achievement> {
+        let mut unlocked = Vec::new();
+        if let Some((reason, death)) = self.pending_end.take() {
+            self.finish(reason, death, &mut unlocked);
+            if !unlocked.is_empty() {
+                self.achievements.save(&self.data_dir);
+            }
+        }
+
+        unlocked
+    }
+
+    pub fn best_score(&self) -> Option<u32> {
+        self.high_scores.best(&self.game.mode, &self.game.settings)
+    }
+
+    pub fn update(&mut self) -> SessionUpdate {
+        if let Some(playback) = &self.playback {
+            playback.queue_inputs(&mut self.game);
+        }
+
+        let previous_game = if self.game.mode.allows_rewind() {
+            Some((self.game.clone(), self.recorder.clone()))
+        } else {
+            None
+        };
+
+        let mut update = SessionUpdate {
+            events: self.game.update(),
+            ..SessionUpdate::default()
+        };
+
+        if let Some(previous_game) = previous_game {
+            // The tick of a crash can be rewound as well
+            if update.events.iter().any(|event| {
+                matches!(
+                    event,
+                    GameEvent::Moved
+                        | GameEvent::Ended {
+                            reason: EndReason::Crashed,
+                            ..
+                        }
+                )
+            }) {
+                self.history.push_back(previous_game);
+                if self.history.len() > config::REWIND_TICKS {
+                    self.history.pop_front();
+                }
+            }
+        }
+
+        for event in update.events.iter() {
+            match event {
+                GameEvent::Turned { from, to } => {
+                    self.recorder.record_turn();
+                    self.track(
+                        AchievementEvent::Turned {
+                            is_left_turn: from.is_left_turn_to(to),
+                        },
+                        &mut update.unlocked,
+                    );
+                }
+                GameEvent::ItemEaten {
+                    kind: ItemKind::Apple | ItemKind::GoldenApple,
+                    tick,
+                    ..
+                } => {
+                    self.recorder.record_apple(*tick);
+                    self.track(
+                        AchievementEvent::AppleEaten { tick: *tick },
+                        &mut update.unlocked,
+                    );
+                }
+                GameEvent::ItemEaten { .. } | GameEvent::ItemSpawned { .. } => {}
+                GameEvent::Moved => {
+                    if self.game.ticks == 1 {
+                        self.start_daily_attempt();
+                    }
+
+                    let progress = self.game.progress();
+                    self.track(AchievementEvent::Progressed(progress), &mut update.unlocked);
+                }
+                // Crashes in practice wait for the player to rewind or move on
+                GameEvent::Ended {
+                    reason: EndReason::Crashed,
+                    death,
+                } if self.game.mode.allows_rewind() && !self.is_playback() => {
+                    self.pending_end = Some((EndReason::Crashed, death.clone()));
+                    self.summary = Some(self.summarize(EndReason::Crashed));
+                }
+                GameEvent::Ended { reason, death } => {
+                    self.finish(*reason, death.clone(), &mut update.unlocked);
+                }
+            }
+        }
+
+        if !update.unlocked.is_empty() {
+            self.achievements.save(&self.data_dir);
+        }
+
+        update
+    }
+
+    fn track(&mut self, event: AchievementEvent, unlocked: &mut Vec<Achievement>) {
+        // Rewound games are flagged and do not count, just like replays
+        if self.recorder.rewound_ticks() > 0 || self.is_playback() {
+            return;
+        }
+
+        for achievement in self.achievement_tracker.handle(&event) {
+            if self.achievements.unlock(&self.player, achievement) {
+                unlocked.push(achievement);
+            }
+        }
+    }
+
+    /// Summary of the game that has ended for the reason, before anything
+    /// is recorded
+    fn summarize(&self, reason: EndReason) -> GameSummary {
+        let mut summary = self.game.mode.summary(reason, &self.game.progress());
+
+        let rewound_ticks = self.recorder.rewound_ticks();
+        if rewound_ticks > 0 {
+            summary.recorded_score = None;
+            summary.details.push(i18n::tr_with(
+                "summary-rewound",
+                &[("ticks", &rewound_ticks)],
+            ));
+        }
+
+        summary
+    }
+
+    fn finish(&mut self, reason: EndReason, death: Option<Death>, unlocked: &mut Vec<Achievement>) {
+        let game = &self.game;
+        let progress = game.progress();
+        let mut summary = self.summarize(reason);
+
+        if self.is_playback() {
+            self.summary = Some(summary);
+            return;
+        }
+        self.replay.save_last(&self.data_dir);
+
+        let rewound_ticks = self.recorder.rewound_ticks();
+
+        if game.mode == GameMode::Daily {
+            self.record_daily(&mut summary, reason);
+        }
+
+        let game = &self.game;
+        let mut high_scores_changed = false;
+        if let Some(score) = summary.recorded_score {
+            high_scores_changed |= self.high_scores.record(&game.mode, &game.settings, score);
+        }
+        if reason == EndReason::Victory && rewound_ticks == 0 {
+            self.high_scores
+                .record_victory(&game.mode, &game.settings, game.ticks);
+            high_scores_changed = true;
+
+            if let Some(victories) = self.high_scores.victories(&game.mode, &game.settings) {
+                summary.details.push(i18n::tr_with(
+                    "summary-victory-count",
+                    &[
+                        ("count", &victories.count),
+                        ("ticks", &victories.fastest_ticks),
+                    ],
+                ));
+            }
+        }
+        if high_scores_changed {
+            self.high_scores.save(&self.data_dir);
+        }
+
+        self.summary = Some(summary);
+
+        self.track(
+            AchievementEvent::GameEnded(reason, progress.clone()),
+            unlocked,
+        );
+        Statistics::record(
+            &self.data_dir,
+            self.recorder.finish(reason, &progress, death),
+        );
+    }
+
+    /// Uses up the attempt at the daily challenge as soon as its first game
+    /// starts, so that restarting doesn't give another try
+    fn start_daily_attempt(&mut self) {
+        if self.game.mode != GameMode::Daily || self.is_playback() {
+            return;
+        }
+
+        if let Some(challenge) = &self.daily {
+            if challenge.is_played_with(&self.game.settings) && self.daily_results.start(challenge)
+            {
+                self.daily_results.save(&self.data_dir);
+                self.daily_attempt = true;
+            }
+        }
+    }
+
+    /// Keeps the score of the attempt at the daily challenge that is left
+    /// before its end
+    fn abandon_daily_attempt(&mut self) {
+        if !std::mem::replace(&mut self.daily_attempt, false) {
+            return;
+        }
+
+        if let Some(challenge) = &self.daily {
+            let result = DailyResult {
+                score: self.game.score,
+                ticks: self.game.ticks,
+                ..DailyResult::new(challenge)
+            };
+            self.daily_results.update(challenge, result);
+            self.daily_results.save(&self.data_dir);
+        }
+    }
+
+    /// Records the end of the attempt at the daily challenge, whose score is
+    /// kept apart from the high scores. Later games of the day are practice.
+    fn record_daily(&mut self, summary: &mut GameSummary, reason: EndReason) {
+        let score = summary.recorded_score.take();
+        let challenge = match &self.daily {
+            Some(challenge) if challenge.is_played_with(&self.game.settings) => challenge,
+            _ => return,
+        };
+
+        if !self.daily_attempt {
+            summary.details.push(i18n::tr_with(
+                "summary-daily-practice",
+                &[("date", &challenge.date)],
+            ));
+            return;
+        }
+        self.daily_attempt = false;
+
+        let result = DailyResult {
+            score: score.unwrap_or(self.game.score),
+            ticks: self.game.ticks,
+            reason: Some(reason),
+            ..DailyResult::new(challenge)
+        };
+        self.daily_results.update(challenge, result.clone());
+        self.daily_results.save(&self.data_dir);
+
+        let text = challenge.summary(&result);
+        daily::write_summary(&self.data_dir, &text);
+        summary.details.push(i18n::tr("summary-daily-recorded"));
+        summary.details.push(text);
+    }
+}
+
+// The profile of restored sessions is loaded from the default directory
+fn load_high_scores() -> HighScores {
+    HighScores::load(&storage::data_dir())
+}
+
+fn load_daily_results() -> DailyResults {
+    DailyResults::load(&storage::data_dir())
+}
+
+fn load_achievements() -> Achievements {
+    Achievements::load(&storage::data_dir())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::daily::Date;
+
+    /// Session whose profile is stored in a temporary directory
+    fn new_session(name: &str, mode: GameMode, settings: Settings) -> GameSession {
+        let dir =
+            std::env::temp_dir().join(format!("snake-session-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        GameSession::with_data_dir(mode, settings, dir)
+    }
+
+    fn run_ticks(session: &mut GameSession, ticks: u64) {
+        while session.game.ticks < ticks && !session.game.is_over() {
+            session.update();
+        }
+    }
+
+    #[test]
+    fn test_rewind_restores_previous_ticks() {
+        let mut session = new_session("rewind", GameMode::Practice, Settings::default());
+        run_ticks(&mut session, 2);
+        let snake = session.game.snake.clone();
+        let apples = session.game.apples.clone();
+
+        run_ticks(&mut session, 4);
+        assert!(session.rewind());
+        assert!(session.rewind());
+
+        assert_eq!(session.game.ticks, 2);
+        assert_eq!(session.game.snake, snake);
+        assert_eq!(session.game.apples, apples);
+        assert_eq!(session.recorder.rewound_ticks(), 2);
+    }
+
+    #[test]
+    fn test_rewind_is_limited_to_practice() {
+        let mut session = new_session("endless", GameMode::Endless, Settings::default());
+        run_ticks(&mut session, 2);
+
+        assert!(!session.rewind());
+        assert_eq!(session.game.ticks, 2);
+    }
+
+    #[test]
+    fn test_rewind_forgets_undone_inputs() {
+        let mut session = new_session("inputs", GameMode::Practice, Settings::default());
+        run_ticks(&mut session, 2);
+        session.queue_direction(Direction::Left);
+        run_ticks(&mut session, 3);
+        session.queue_direction(Direction::Up);
+        assert_eq!(session.replay.inputs.len(), 2);
+
+        assert!(session.rewind());
+        assert_eq!(session.replay.inputs.len(), 1);
+
+        assert!(session.rewind());
+        assert!(session.replay.inputs.is_empty());
+    }
+
+    #[test]
+    fn test_rewind_restores_the_statistics() {
+        let mut session = new_session("statistics", GameMode::Practice, Settings::default());
+        session.game = Game::from_board(
+            GameMode::Practice,
+            Settings::default(),
+            &"
+            direction: up
+            .....
+            ..*..
+            .....
+            .*...
+            ..H..
+            .....
+            "
+            .parse()
+            .unwrap(),
+        );
+
+        // The apple ahead is eaten in the third tick
+        run_ticks(&mut session, 3);
+        assert_eq!(session.game.apples_eaten, 1);
+
+        for _ in 0..2 {
+            assert!(session.rewind());
+        }
+        assert_eq!(session.game.ticks, 1);
+
+        // The other apple is eaten in an earlier tick than the undone one
+        session.queue_direction(Direction::Left);
+        run_ticks(&mut session, 2);
+        assert_eq!(session.game.apples_eaten, 1);
+
+        let record = session
+            .recorder
+            .finish(EndReason::Crashed, &session.game.progress(), None);
+        assert_eq!(record.turns, 1);
+        assert_eq!(record.apple_intervals, vec![1]);
+        assert_eq!(record.rewound_ticks, 2);
+    }
+
+    #[test]
+    fn test_rewind_undoes_crashes_in_practice() {
+        let mut session = new_session("crash", GameMode::Practice, Settings::default());
+        run_ticks(&mut session, u64::MAX);
+        assert_eq!(session.game.end, Some(EndReason::Crashed));
+        assert!(session.summary.is_some());
+        assert!(session.can_rewind());
+
+        // The crash doesn't count as a tick, but it is undone with its update
+        let updates = session.game.updates();
+        assert!(session.rewind());
+        assert!(!session.game.is_over());
+        assert!(session.summary.is_none());
+        assert!(session.game.updates() < updates);
+    }
+
+    #[test]
+    fn test_restarted_daily_attempt_counts() {
+        let challenge = DailyChallenge::new(Date::from_days(20_000), &Settings::default());
+        let mut session = new_session("daily", GameMode::Daily, challenge.settings.clone());
+        session.use_daily(Some(challenge.clone()));
+
+        // Restarting before the first move doesn't use up the attempt
+        session.restart();
+        assert_eq!(session.daily_results.get(&challenge), None);
+
+        run_ticks(&mut session, 2);
+        assert!(session.daily_attempt);
+        assert!(session.daily_results.get(&challenge).is_some());
+
+        session.restart();
+        let result = DailyResult {
+            score: session.game.score,
+            ticks: 2,
+            ..DailyResult::new(&challenge)
+        };
+        assert_eq!(session.daily_results.get(&challenge), Some(&result));
+
+        // Further games of the day are practice
+        run_ticks(&mut session, 2);
+        assert!(!session.daily_attempt);
+        assert_eq!(session.daily_results.get(&challenge), Some(&result));
+    }
+
+    #[test]
+    fn test_playback_ignores_inputs() {
+        let session = new_session("playback", GameMode::Endless, Settings::default());
+        let mut playback = GameSession::from_replay(session.replay.clone());
+
+        playback.queue_direction(Direction::Left);
+        run_ticks(&mut playback, 1);
+
+        assert_eq!(playback.game.snake.direction, Direction::Up);
+        assert_eq!(playback.game.apples, session.game.apples);
+    }
+}
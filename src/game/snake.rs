@@ -0,0 +1,62 @@
+use super::Direction;
+use serde::{Deserialize, Serialize};
+use vek::Vec2;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Snake {
+    pub head: Vec2<i32>,
+    pub tail: Vec<Vec2<i32>>,
+    pub direction: Direction,
+}
+
+impl Snake {
+    pub fn new(head: Vec2<i32>) -> Snake {
+        Snake {
+            head,
+            tail: Vec::new(),
+            direction: Direction::Up,
+        }
+    }
+
+    pub fn move_forward(&mut self) {
+        let head = self.head;
+        let mut positions = self.tail.iter_mut().rev().peekable();
+        while let Some(position) = positions.next() {
+            let prev_position = positions.peek().map_or(head, |position| **position);
+            *position = prev_position;
+        }
+
+        self.head = self.get_next_head_position();
+    }
+
+    pub fn get_next_head_position(&self) -> Vec2<i32> {
+        let mut head_pos = self.head;
+
+        match self.direction {
+            Direction::Up => head_pos.y -= 1,
+            Direction::Down => head_pos.y += 1,
+            Direction::Left => head_pos.x -= 1,
+            Direction::Right => head_pos.x += 1,
+        }
+
+        head_pos
+    }
+
+    pub fn grow_tail(&mut self) {
+        let mut position = *self.tail.last().unwrap_or(&self.head);
+
+        match self.direction {
+            Direction::Up => position.y += 1,
+            Direction::Down => position.y -= 1,
+            Direction::Left => position.x += 1,
+            Direction::Right => position.x -= 1,
+        }
+
+        self.tail.push(position);
+    }
+
+    pub fn shrink_tail(&mut self, amount: usize) {
+        let new_len = self.tail.len().saturating_sub(amount);
+        self.tail.truncate(new_len);
+    }
+}
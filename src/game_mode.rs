@@ -0,0 +1,397 @@
+use crate::i18n;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Copy, Clone, Debug, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum GameMode {
+    /// Survive as long as possible
+    #[default]
+    Endless,
+    /// Collect as many points as possible within the given time
+    TimeAttack { seconds: u32 },
+    /// Reach the given snake length in as few ticks as possible
+    TargetLength { length: u32 },
+    /// Collect as many points as possible with the given amount of moves
+    LimitedMoves { moves: u32 },
+    /// Endless game in which the last seconds can be rewound
+    Practice,
+    /// Endless game with the settings of the daily challenge, of which the
+    /// first game of the day counts
+    Daily,
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Copy, Clone, Debug)]
+#[serde(rename_all = "kebab-case")]
+pub enum EndReason {
+    Crashed,
+    TimeUp,
+    TargetReached,
+    OutOfMoves,
+    /// The snake filled the whole board
+    Victory,
+}
+
+/// Snapshot of the values that modes base their end condition and score on
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct GameProgress {
+    pub ticks: u64,
+    pub elapsed_seconds: f64,
+    pub length: u32,
+    pub score: u32,
+}
+
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct GameSummary {
+    pub title: String,
+    pub score_label: String,
+    pub score: u32,
+    pub details: Vec<String>,
+    /// Score that should be recorded, if the run counts
+    pub recorded_score: Option<u32>,
+}
+
+impl EndReason {
+    pub fn name(&self) -> &'static str {
+        match self {
+            EndReason::Crashed => "crashed",
+            EndReason::TimeUp => "time-up",
+            EndReason::TargetReached => "target-reached",
+            EndReason::OutOfMoves => "out-of-moves",
+            EndReason::Victory => "victory",
+        }
+    }
+}
+
+impl GameMode {
+    pub const PRESETS: [GameMode; 6] = [
+        GameMode::Endless,
+        GameMode::TimeAttack { seconds: 60 },
+        GameMode::TimeAttack { seconds: 120 },
+        GameMode::TargetLength { length: 25 },
+        GameMode::LimitedMoves { moves: 200 },
+        GameMode::Practice,
+    ];
+
+    /// Identifies the mode in the high scores; endless games have no key to
+    /// keep the scores recorded before modes existed.
+    pub fn key(&self) -> Option<String> {
+        match self {
+            GameMode::Endless => None,
+            GameMode::TimeAttack { seconds } => Some(format!("time-attack={}", seconds)),
+            GameMode::TargetLength { length } => Some(format!("target-length={}", length)),
+            GameMode::LimitedMoves { moves } => Some(format!("limited-moves={}", moves)),
+            GameMode::Practice => Some("practice".to_string()),
+            GameMode::Daily => Some("daily".to_string()),
+        }
+    }
+
+    pub fn allows_rewind(&self) -> bool {
+        matches!(self, GameMode::Practice)
+    }
+
+    /// Whether a lower score is the better one, e.g. fewer ticks in a race
+    pub fn lower_is_better(&self) -> bool {
+        matches!(self, GameMode::TargetLength { .. })
+    }
+
+    pub fn check_end(&self, progress: &GameProgress) -> Option<EndReason> {
+        match *self {
+            GameMode::Endless | GameMode::Practice | GameMode::Daily => None,
+            GameMode::TimeAttack { seconds } => {
+                if progress.elapsed_seconds >= seconds as f64 {
+                    Some(EndReason::TimeUp)
+                } else {
+                    None
+                }
+            }
+            GameMode::TargetLength { length } => {
+                if progress.length >= length {
+                    Some(EndReason::TargetReached)
+                } else {
+                    None
+                }
+            }
+            GameMode::LimitedMoves { moves } => {
+                if progress.ticks >= moves as u64 {
+                    Some(EndReason::OutOfMoves)
+                } else {
+                    None
+                }
+            }
+        }
+    }
+
+    /// Identifies the mode independently of the language
+    pub fn id(&self) -> String {
+        self.key().unwrap_or_else(|| "endless".to_string())
+    }
+
+    /// Mode specific value that is shown in the HUD
+    pub fn status(&self, progress: &GameProgress) -> Option<(String, String)> {
+        match *self {
+            GameMode::Endless | GameMode::Practice | GameMode::Daily => None,
+            GameMode::TimeAttack { seconds } => {
+                let remaining = (seconds as f64 - progress.elapsed_seconds).max(0.0).ceil() as u32;
+                Some((
+                    i18n::tr("time-left"),
+                    format!("{}:{:02}", remaining / 60, remaining % 60),
+                ))
+            }
+            GameMode::TargetLength { length } => Some((
+                i18n::tr("target"),
+                format!("{} / {}", progress.length, length),
+            )),
+            GameMode::LimitedMoves { moves } => Some((
+                i18n::tr("moves-left"),
+                (moves as u64).saturating_sub(progress.ticks).to_string(),
+            )),
+        }
+    }
+
+    pub fn summary(&self, reason: EndReason, progress: &GameProgress) -> GameSummary {
+        let title = match reason {
+            EndReason::Crashed => "game-over",
+            EndReason::TimeUp => "time-up",
+            EndReason::TargetReached => "target-reached",
+            EndReason::OutOfMoves => "out-of-moves",
+            EndReason::Victory => "victory",
+        };
+        let seconds = format!("{:.1}", progress.elapsed_seconds);
+
+        let mut summary = GameSummary {
+            title: i18n::tr(title),
+            score_label: i18n::tr("score"),
+            score: progress.score,
+            details: vec![i18n::tr_with("summary-mode", &[("mode", self)])],
+            recorded_score: Some(progress.score),
+        };
+
+        match *self {
+            GameMode::Endless
+            | GameMode::LimitedMoves { .. }
+            | GameMode::Practice
+            | GameMode::Daily => {}
+            GameMode::TimeAttack { .. } => {
+                summary
+                    .details
+                    .push(i18n::tr_with("summary-survived", &[("seconds", &seconds)]));
+            }
+            GameMode::TargetLength { length } => {
+                if progress.length >= length {
+                    summary.score_label = i18n::tr("ticks");
+                    summary.score = progress.ticks as u32;
+                    summary.recorded_score = Some(progress.ticks as u32);
+                    summary.details.push(i18n::tr_with(
+                        "summary-target-reached",
+                        &[("length", &length), ("seconds", &seconds)],
+                    ));
+                } else {
+                    summary.score_label = i18n::tr("length");
+                    summary.score = progress.length;
+                    summary.recorded_score = None;
+                    summary.details.push(i18n::tr_with(
+                        "summary-target-missed",
+                        &[("length", &length)],
+                    ));
+                }
+            }
+        }
+
+        if reason == EndReason::Victory {
+            summary.details.push(i18n::tr_with(
+                "summary-victory",
+                &[("ticks", &progress.ticks), ("seconds", &seconds)],
+            ));
+        }
+
+        summary
+    }
+}
+
+impl fmt::Display for GameMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GameMode::Endless => write!(f, "{}", i18n::tr("mode-endless")),
+            GameMode::TimeAttack { seconds } => write!(
+                f,
+                "{}",
+                i18n::tr_with("mode-time-attack", &[("seconds", seconds)])
+            ),
+            GameMode::TargetLength { length } => write!(
+                f,
+                "{}",
+                i18n::tr_with("mode-target-length", &[("length", length)])
+            ),
+            GameMode::LimitedMoves { moves } => write!(
+                f,
+                "{}",
+                i18n::tr_with("mode-limited-moves", &[("moves", moves)])
+            ),
+            GameMode::Practice => write!(f, "{}", i18n::tr("mode-practice")),
+            GameMode::Daily => write!(f, "{}", i18n::tr("mode-daily")),
+        }
+    }
+}
+
+impl FromStr for GameMode {
+    type Err = String;
+
+    /// Parses a mode in the format of its key, e.g. `time-attack=90`. The
+    /// value may be left out to use the one of the first preset.
+    fn from_str(s: &str) -> Result<GameMode, String> {
+        let (name, value) = match s.split_once('=') {
+            Some((name, value)) => (name, Some(value)),
+            None => (s, None),
+        };
+
+        let preset = GameMode::PRESETS
+            .iter()
+            .find(|mode| {
+                let key = mode.key().unwrap_or_else(|| "endless".to_string());
+                key.split('=').next() == Some(name)
+            })
+            .ok_or_else(|| format!("unknown mode '{}'", name))?;
+
+        let value = match value {
+            Some(value) => value
+                .parse::<u32>()
+                .map_err(|err| format!("invalid value '{}' of mode '{}': {}", value, name, err))?,
+            None => return Ok(*preset),
+        };
+        // The game would end right away or could never be won
+        if value == 0 {
+            return Err(format!("the value of mode '{}' needs to be above 0", name));
+        }
+
+        match preset {
+            GameMode::TimeAttack { .. } => Ok(GameMode::TimeAttack { seconds: value }),
+            GameMode::TargetLength { .. } => Ok(GameMode::TargetLength { length: value }),
+            GameMode::LimitedMoves { .. } => Ok(GameMode::LimitedMoves { moves: value }),
+            GameMode::Endless | GameMode::Practice | GameMode::Daily => {
+                Err(format!("mode '{}' takes no value", name))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!("endless".parse(), Ok(GameMode::Endless));
+        assert_eq!("practice".parse(), Ok(GameMode::Practice));
+        assert_eq!(
+            "time-attack".parse(),
+            Ok(GameMode::TimeAttack { seconds: 60 })
+        );
+        assert_eq!(
+            "limited-moves=50".parse(),
+            Ok(GameMode::LimitedMoves { moves: 50 })
+        );
+        assert!("endless=3".parse::<GameMode>().is_err());
+        assert!("target-length=many".parse::<GameMode>().is_err());
+        assert!("time-attack=0".parse::<GameMode>().is_err());
+        assert!("target-length=0".parse::<GameMode>().is_err());
+        assert!("limited-moves=0".parse::<GameMode>().is_err());
+        assert!("snail".parse::<GameMode>().is_err());
+
+        for mode in GameMode::PRESETS.iter() {
+            if let Some(key) = mode.key() {
+                assert_eq!(key.parse(), Ok(*mode));
+            }
+        }
+    }
+
+    fn progress(ticks: u64, elapsed_seconds: f64, length: u32, score: u32) -> GameProgress {
+        GameProgress {
+            ticks,
+            elapsed_seconds,
+            length,
+            score,
+        }
+    }
+
+    #[test]
+    fn test_endless_never_ends() {
+        let mode = GameMode::Endless;
+
+        assert_eq!(mode.check_end(&progress(100_000, 5000.0, 100, 99)), None);
+    }
+
+    #[test]
+    fn test_time_attack() {
+        let mode = GameMode::TimeAttack { seconds: 60 };
+
+        assert_eq!(mode.check_end(&progress(170, 59.9, 5, 4)), None);
+        assert_eq!(
+            mode.check_end(&progress(180, 60.0, 5, 4)),
+            Some(EndReason::TimeUp)
+        );
+
+        let summary = mode.summary(EndReason::TimeUp, &progress(180, 60.0, 5, 4));
+        assert_eq!(summary.score, 4);
+        assert_eq!(summary.recorded_score, Some(4));
+    }
+
+    #[test]
+    fn test_target_length() {
+        let mode = GameMode::TargetLength { length: 10 };
+
+        assert_eq!(mode.check_end(&progress(50, 16.0, 9, 8)), None);
+        assert_eq!(
+            mode.check_end(&progress(51, 17.0, 10, 9)),
+            Some(EndReason::TargetReached)
+        );
+        assert!(mode.lower_is_better());
+
+        let summary = mode.summary(EndReason::TargetReached, &progress(51, 17.0, 10, 9));
+        assert_eq!(summary.score, 51);
+        assert_eq!(summary.recorded_score, Some(51));
+
+        let summary = mode.summary(EndReason::Crashed, &progress(30, 10.0, 7, 6));
+        assert_eq!(summary.recorded_score, None);
+    }
+
+    #[test]
+    fn test_limited_moves() {
+        let mode = GameMode::LimitedMoves { moves: 200 };
+
+        assert_eq!(mode.check_end(&progress(199, 66.0, 3, 2)), None);
+        assert_eq!(
+            mode.check_end(&progress(200, 66.7, 3, 2)),
+            Some(EndReason::OutOfMoves)
+        );
+        assert_eq!(
+            mode.status(&progress(150, 50.0, 3, 2)),
+            Some(("Moves left".to_string(), "50".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_victory() {
+        let summary =
+            GameMode::Endless.summary(EndReason::Victory, &progress(900, 300.0, 135, 134));
+
+        assert_eq!(summary.title, "Victory");
+        assert_eq!(summary.recorded_score, Some(134));
+        assert_eq!(summary.details.len(), 2);
+
+        let mode = GameMode::TargetLength { length: 200 };
+        let summary = mode.summary(EndReason::Victory, &progress(900, 300.0, 135, 134));
+
+        assert_eq!(summary.recorded_score, None);
+    }
+
+    #[test]
+    fn test_endless_key_is_empty() {
+        assert_eq!(GameMode::Endless.key(), None);
+        assert!(GameMode::PRESETS
+            .iter()
+            .skip(1)
+            .all(|mode| mode.key().is_some()));
+    }
+}
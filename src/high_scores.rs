@@ -0,0 +1,161 @@
+use crate::game_mode::GameMode;
+use crate::settings::Settings;
+use crate::storage;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+const FILE_NAME: &str = "high-scores.json";
+
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+pub struct HighScores {
+    best: HashMap<String, u32>,
+    #[serde(default)]
+    victories: HashMap<String, Victories>,
+}
+
+/// Games in which the whole board has been filled
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+pub struct Victories {
+    pub count: u32,
+    pub fastest_ticks: u64,
+}
+
+impl HighScores {
+    pub fn load(dir: &Path) -> HighScores {
+        storage::load(dir, FILE_NAME).unwrap_or_else(|err| {
+            eprintln!("Unable to load high scores: {}", err);
+            HighScores::default()
+        })
+    }
+
+    pub fn save(&self, dir: &Path) {
+        if let Err(err) = storage::save(dir, FILE_NAME, self) {
+            eprintln!("Unable to save high scores: {}", err);
+        }
+    }
+
+    /// Identifies the mode and settings a score was achieved with
+    pub fn key(mode: &GameMode, settings: &Settings) -> String {
+        match mode.key() {
+            Some(mode_key) => format!("{},{}", mode_key, settings.key()),
+            None => settings.key(),
+        }
+    }
+
+    pub fn best(&self, mode: &GameMode, settings: &Settings) -> Option<u32> {
+        self.best.get(&HighScores::key(mode, settings)).cloned()
+    }
+
+    /// Records a score and returns whether it is a new best score.
+    pub fn record(&mut self, mode: &GameMode, settings: &Settings, score: u32) -> bool {
+        let key = HighScores::key(mode, settings);
+
+        let is_better = match self.best.get(&key) {
+            Some(&best) if mode.lower_is_better() => score < best,
+            Some(&best) => score > best,
+            None => true,
+        };
+        if is_better {
+            self.best.insert(key, score);
+        }
+
+        is_better
+    }
+
+    pub fn victories(&self, mode: &GameMode, settings: &Settings) -> Option<&Victories> {
+        self.victories.get(&HighScores::key(mode, settings))
+    }
+
+    /// Records a victory and returns whether it is the fastest one.
+    pub fn record_victory(&mut self, mode: &GameMode, settings: &Settings, ticks: u64) -> bool {
+        let victories = self
+            .victories
+            .entry(HighScores::key(mode, settings))
+            .or_default();
+
+        victories.count += 1;
+        if victories.count > 1 && ticks >= victories.fastest_ticks {
+            return false;
+        }
+
+        victories.fastest_ticks = ticks;
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record() {
+        let mode = GameMode::Endless;
+        let settings = Settings::default();
+        let mut high_scores = HighScores::default();
+
+        assert_eq!(high_scores.best(&mode, &settings), None);
+        assert!(high_scores.record(&mode, &settings, 5));
+        assert!(!high_scores.record(&mode, &settings, 3));
+        assert!(!high_scores.record(&mode, &settings, 5));
+        assert_eq!(high_scores.best(&mode, &settings), Some(5));
+    }
+
+    #[test]
+    fn test_record_lower_is_better() {
+        let mode = GameMode::TargetLength { length: 10 };
+        let settings = Settings::default();
+        let mut high_scores = HighScores::default();
+
+        assert!(high_scores.record(&mode, &settings, 80));
+        assert!(high_scores.record(&mode, &settings, 60));
+        assert!(!high_scores.record(&mode, &settings, 70));
+        assert_eq!(high_scores.best(&mode, &settings), Some(60));
+    }
+
+    #[test]
+    fn test_record_victory() {
+        let mode = GameMode::Endless;
+        let settings = Settings::default();
+        let mut high_scores = HighScores::default();
+
+        assert_eq!(high_scores.victories(&mode, &settings), None);
+        assert!(high_scores.record_victory(&mode, &settings, 900));
+        assert!(!high_scores.record_victory(&mode, &settings, 1000));
+        assert!(high_scores.record_victory(&mode, &settings, 800));
+        assert_eq!(
+            high_scores.victories(&mode, &settings),
+            Some(&Victories {
+                count: 3,
+                fastest_ticks: 800,
+            })
+        );
+    }
+
+    #[test]
+    fn test_record_per_settings() {
+        let default_settings = Settings::default();
+        let other_settings = Settings {
+            apple_count: 3,
+            ..Settings::default()
+        };
+        let mut high_scores = HighScores::default();
+
+        high_scores.record(&GameMode::Endless, &default_settings, 7);
+        high_scores.record(&GameMode::Endless, &other_settings, 2);
+
+        assert_eq!(
+            high_scores.best(&GameMode::Endless, &default_settings),
+            Some(7)
+        );
+        assert_eq!(
+            high_scores.best(&GameMode::Endless, &other_settings),
+            Some(2)
+        );
+        assert_eq!(
+            high_scores.best(&GameMode::TimeAttack { seconds: 60 }, &default_settings),
+            None
+        );
+    }
+}
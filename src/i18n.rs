@@ -0,0 +1,166 @@
+use crate::assets;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::OnceLock;
+
+pub const DEFAULT_LANGUAGE: &str = "en";
+
+/// Built-in messages that are used whenever a message is missing in the catalog
+/// of the selected language.
+const FALLBACK_MESSAGES: &str = include_str!("../assets/locales/en.txt");
+
+static CATALOG: OnceLock<Catalog> = OnceLock::new();
+
+#[derive(Clone, Debug, Default)]
+pub struct Catalog {
+    messages: HashMap<String, String>,
+    fallback: HashMap<String, String>,
+}
+
+impl Catalog {
+    pub fn parse(content: &str) -> Catalog {
+        Catalog {
+            messages: parse_messages(content),
+            fallback: parse_messages(FALLBACK_MESSAGES),
+        }
+    }
+
+    /// Loads the catalog of a language from the assets, falling back to
+    /// English if there is none.
+    pub fn load(language: &str) -> Catalog {
+        match assets::load_text(&format!("locales/{}.txt", language)) {
+            Ok(content) => Catalog::parse(&content),
+            Err(err) => {
+                if language != DEFAULT_LANGUAGE {
+                    eprintln!("Unable to load language '{}': {}", language, err);
+                }
+                Catalog::parse(FALLBACK_MESSAGES)
+            }
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.messages
+            .get(key)
+            .or_else(|| self.fallback.get(key))
+            .map(String::as_str)
+    }
+
+    pub fn translate(&self, key: &str, args: &[(&str, &dyn Display)]) -> String {
+        let mut message = match self.get(key) {
+            Some(message) => message.to_string(),
+            None => return key.to_string(),
+        };
+
+        for (name, value) in args {
+            message = message.replace(&format!("{{{}}}", name), &value.to_string());
+        }
+
+        message
+    }
+}
+
+fn parse_messages(content: &str) -> HashMap<String, String> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let mut parts = line.splitn(2, '=');
+            let key = parts.next()?.trim();
+            let value = parts.next()?.trim();
+
+            Some((key.to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+/// Selects the language of all messages. Has no effect once a message has
+/// been translated.
+pub fn init(language: &str) {
+    let _ = CATALOG.set(Catalog::load(language));
+}
+
+fn catalog() -> &'static Catalog {
+    CATALOG.get_or_init(|| Catalog::parse(FALLBACK_MESSAGES))
+}
+
+/// Translates the message with the given key into the selected language.
+pub fn tr(key: &str) -> String {
+    catalog().translate(key, &[])
+}
+
+/// Translates the message with the given key and fills in its placeholders.
+pub fn tr_with(key: &str, args: &[(&str, &dyn Display)]) -> String {
+    catalog().translate(key, args)
+}
+
+/// Asset path of the font that covers the script of the selected language
+#[cfg(feature = "window")]
+pub fn font() -> String {
+    catalog()
+        .get("font")
+        .unwrap_or(assets::DEFAULT_FONT)
+        .to_string()
+}
+
+/// Detects the language of the system from the usual locale variables,
+/// e.g. `de` for `LANG=de_DE.UTF-8`.
+pub fn detect_language() -> String {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|locale| !locale.is_empty())
+        .map(|locale| language_of_locale(&locale))
+        .unwrap_or_else(|| DEFAULT_LANGUAGE.to_string())
+}
+
+fn language_of_locale(locale: &str) -> String {
+    let language = locale
+        .split(&['_', '.', '@', '-'][..])
+        .next()
+        .unwrap_or_default()
+        .to_lowercase();
+
+    match language.as_str() {
+        "" | "c" | "posix" => DEFAULT_LANGUAGE.to_string(),
+        _ => language,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_translate() {
+        let catalog = Catalog::parse("# Comment\npaused = Pausa\ngreeting = Hi {name}!");
+
+        assert_eq!(catalog.translate("paused", &[]), "Pausa");
+        assert_eq!(
+            catalog.translate("greeting", &[("name", &"Ann")]),
+            "Hi Ann!"
+        );
+        assert_eq!(catalog.translate("game-over", &[]), "Game over");
+        assert_eq!(catalog.translate("does-not-exist", &[]), "does-not-exist");
+    }
+
+    #[test]
+    fn test_language_of_locale() {
+        assert_eq!(language_of_locale("de_DE.UTF-8"), "de");
+        assert_eq!(language_of_locale("ja"), "ja");
+        assert_eq!(language_of_locale("pt-BR"), "pt");
+        assert_eq!(language_of_locale("C"), "en");
+        assert_eq!(language_of_locale("POSIX"), "en");
+    }
+
+    #[test]
+    fn test_catalogs_are_complete() {
+        let english = parse_messages(FALLBACK_MESSAGES);
+        let german = parse_messages(include_str!("../assets/locales/de.txt"));
+
+        for key in english.keys() {
+            assert!(german.contains_key(key), "Missing German message '{}'", key);
+        }
+    }
+}
@@ -0,0 +1,194 @@
+use crate::color::Color;
+use crate::config;
+use crate::i18n;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Hash, Copy, Clone, Debug)]
+#[serde(rename_all = "kebab-case")]
+pub enum ItemKind {
+    Apple,
+    GoldenApple,
+    Poison,
+    SpeedUp,
+    SlowDown,
+    Ghost,
+    Shrink,
+}
+
+impl ItemKind {
+    pub const ALL: [ItemKind; 7] = [
+        ItemKind::Apple,
+        ItemKind::GoldenApple,
+        ItemKind::Poison,
+        ItemKind::SpeedUp,
+        ItemKind::SlowDown,
+        ItemKind::Ghost,
+        ItemKind::Shrink,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ItemKind::Apple => "apple",
+            ItemKind::GoldenApple => "golden",
+            ItemKind::Poison => "poison",
+            ItemKind::SpeedUp => "speed-up",
+            ItemKind::SlowDown => "slow-down",
+            ItemKind::Ghost => "ghost",
+            ItemKind::Shrink => "shrink",
+        }
+    }
+
+    pub fn label(&self) -> String {
+        i18n::tr(&format!("item-{}", self.name()))
+    }
+
+    pub fn color(&self) -> Color {
+        match self {
+            ItemKind::Apple => config::APPLE_COLOR,
+            ItemKind::GoldenApple => config::GOLDEN_APPLE_COLOR,
+            ItemKind::Poison => config::POISON_COLOR,
+            ItemKind::SpeedUp => config::SPEED_UP_COLOR,
+            ItemKind::SlowDown => config::SLOW_DOWN_COLOR,
+            ItemKind::Ghost => config::GHOST_COLOR,
+            ItemKind::Shrink => config::SHRINK_COLOR,
+        }
+    }
+
+    /// Whether eating the item activates an effect that lasts for a while
+    pub fn has_effect(&self) -> bool {
+        matches!(
+            self,
+            ItemKind::SpeedUp | ItemKind::SlowDown | ItemKind::Ghost
+        )
+    }
+
+    /// Factor by which an active effect of this kind changes the snake speed
+    pub fn speed_factor(&self) -> f64 {
+        match self {
+            ItemKind::SpeedUp => config::SPEED_UP_FACTOR,
+            ItemKind::SlowDown => config::SLOW_DOWN_FACTOR,
+            _ => 1.0,
+        }
+    }
+}
+
+impl fmt::Display for ItemKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for ItemKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ItemKind::ALL
+            .iter()
+            .find(|kind| kind.name() == s)
+            .cloned()
+            .ok_or_else(|| format!("Unknown item kind '{}'", s))
+    }
+}
+
+/// Assigns a number to each item kind, e.g. spawn weights or durations.
+///
+/// Can be parsed from a comma separated list like `golden=2,poison=0`, where
+/// unlisted kinds keep their default value.
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+pub struct ItemTable {
+    values: [u32; 7],
+}
+
+impl ItemTable {
+    pub const fn new(values: [u32; 7]) -> ItemTable {
+        ItemTable { values }
+    }
+
+    pub fn get(&self, kind: ItemKind) -> u32 {
+        self.values[Self::index(kind)]
+    }
+
+    pub fn set(&mut self, kind: ItemKind, value: u32) {
+        self.values[Self::index(kind)] = value;
+    }
+
+    pub fn parse_with_defaults(s: &str, defaults: &ItemTable) -> Result<ItemTable, String> {
+        let mut table = defaults.clone();
+
+        for entry in s
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+        {
+            let mut parts = entry.splitn(2, '=');
+            let kind = parts
+                .next()
+                .unwrap_or_default()
+                .trim()
+                .parse::<ItemKind>()?;
+            let value = parts
+                .next()
+                .ok_or_else(|| format!("Missing value for '{}'", kind))?
+                .trim()
+                .parse::<u32>()
+                .map_err(|err| format!("Invalid value for '{}': {}", kind, err))?;
+
+            table.set(kind, value);
+        }
+
+        Ok(table)
+    }
+
+    fn index(kind: ItemKind) -> usize {
+        ItemKind::ALL
+            .iter()
+            .position(|other| other == &kind)
+            .expect("Item kind is missing in ItemKind::ALL")
+    }
+}
+
+impl fmt::Display for ItemTable {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let entries: Vec<String> = ItemKind::ALL
+            .iter()
+            .map(|kind| format!("{}={}", kind, self.get(*kind)))
+            .collect();
+
+        write!(f, "{}", entries.join(","))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_kind() {
+        for kind in ItemKind::ALL.iter() {
+            assert_eq!(kind.name().parse::<ItemKind>(), Ok(*kind));
+        }
+
+        assert!("banana".parse::<ItemKind>().is_err());
+    }
+
+    #[test]
+    fn test_parse_table() {
+        let defaults = ItemTable::new([1, 2, 3, 4, 5, 6, 7]);
+
+        let table = ItemTable::parse_with_defaults("golden=10, shrink=0", &defaults)
+            .expect("Unable to parse table");
+
+        assert_eq!(table.get(ItemKind::Apple), 1);
+        assert_eq!(table.get(ItemKind::GoldenApple), 10);
+        assert_eq!(table.get(ItemKind::Shrink), 0);
+
+        assert_eq!(
+            ItemTable::parse_with_defaults("", &defaults),
+            Ok(defaults.clone())
+        );
+        assert!(ItemTable::parse_with_defaults("golden", &defaults).is_err());
+        assert!(ItemTable::parse_with_defaults("golden=x", &defaults).is_err());
+    }
+}
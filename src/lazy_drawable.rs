@@ -4,13 +4,80 @@ use std::ops::{Deref, DerefMut};
 use tetra::math::Vec2;
 use tetra::{graphics, Context};
 
+/// Value that remembers whether it has been changed since it was last taken
+/// care of, e.g. rendered.
+///
+/// Every mutable access through `DerefMut` counts as a change. `set` and
+/// `modify` only count actual changes, for values that are updated more
+/// often than they change.
+#[derive(Clone, Debug)]
+pub struct Tracked<T> {
+    value: T,
+    changed: bool,
+}
+
+impl<T> Tracked<T> {
+    /// New values count as changed, so that they are rendered once
+    pub fn new(value: T) -> Tracked<T> {
+        Tracked {
+            value,
+            changed: true,
+        }
+    }
+
+    /// Returns whether the value has been changed and resets the flag
+    pub fn take_changed(&mut self) -> bool {
+        std::mem::replace(&mut self.changed, false)
+    }
+
+    /// Replaces the value if it differs from the current one
+    pub fn set(&mut self, value: T)
+    where
+        T: PartialEq,
+    {
+        if self.value != value {
+            self.value = value;
+            self.changed = true;
+        }
+    }
+
+    /// Lets the closure mutate the value, which returns whether it changed
+    /// something
+    pub fn modify<F: FnOnce(&mut T) -> bool>(&mut self, modify: F) -> bool {
+        let changed = modify(&mut self.value);
+        self.changed |= changed;
+
+        changed
+    }
+}
+
+impl<T> Deref for Tracked<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+impl<T> DerefMut for Tracked<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.changed = true;
+
+        &mut self.value
+    }
+}
+
+/// Renders its content onto a canvas, which is only redrawn after the
+/// content has been changed.
+///
+/// The content is reached through its `Tracked` value, so that helpers of
+/// tracked values work on lazy drawables as well.
 #[derive(Clone)]
 pub struct LazyDrawable<T>
 where
     T: Drawable,
 {
-    pub inner: T,
-    pub updated: bool,
+    content: Tracked<T>,
     canvas: graphics::Canvas,
     canvas_pos: Vec2<f32>,
 }
@@ -25,22 +92,32 @@ where
         canvas_pos: P,
     ) -> LazyDrawable<T> {
         LazyDrawable {
-            inner: obj,
-            updated: true,
+            content: Tracked::new(obj),
             canvas,
             canvas_pos: canvas_pos.into().unwrap_or_else(|| Vec2::new(0.0, 0.0)),
         }
     }
+
+    pub fn set(&mut self, obj: T)
+    where
+        T: PartialEq,
+    {
+        self.content.set(obj);
+    }
+
+    pub fn modify<F: FnOnce(&mut T) -> bool>(&mut self, modify: F) -> bool {
+        self.content.modify(modify)
+    }
 }
 
 impl<T> Deref for LazyDrawable<T>
 where
     T: Drawable,
 {
-    type Target = T;
+    type Target = Tracked<T>;
 
     fn deref(&self) -> &Self::Target {
-        &self.inner
+        &self.content
     }
 }
 
@@ -49,7 +126,7 @@ where
     T: Drawable,
 {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.inner
+        &mut self.content
     }
 }
 
@@ -58,13 +135,12 @@ where
     T: Drawable,
 {
     fn draw(&mut self, ctx: &mut Context) -> tetra::Result {
-        if self.updated {
+        if self.content.take_changed() {
             graphics::set_canvas(ctx, &self.canvas);
             graphics::clear(ctx, Color::transparent().into());
-            self.inner.draw(ctx)?;
+            // Drawing only caches fonts and must not count as a change
+            self.content.value.draw(ctx)?;
             graphics::reset_canvas(ctx);
-
-            self.updated = false;
         }
 
         graphics::draw(ctx, &self.canvas, self.canvas_pos);
@@ -72,3 +148,77 @@ where
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone, Debug, Default, PartialEq)]
+    struct Counter {
+        count: u32,
+    }
+
+    impl Counter {
+        fn increment_below(&mut self, limit: u32) -> bool {
+            if self.count >= limit {
+                return false;
+            }
+
+            self.count += 1;
+            true
+        }
+    }
+
+    #[test]
+    fn test_new_value_is_rendered_once() {
+        let mut tracked = Tracked::new(Counter::default());
+
+        assert!(tracked.take_changed());
+        assert!(!tracked.take_changed());
+    }
+
+    #[test]
+    fn test_reading_is_no_change() {
+        let mut tracked = Tracked::new(Counter::default());
+        tracked.take_changed();
+
+        assert_eq!(tracked.count, 0);
+        assert!(!tracked.take_changed());
+    }
+
+    #[test]
+    fn test_mutation_is_a_change() {
+        let mut tracked = Tracked::new(Counter::default());
+        tracked.take_changed();
+
+        tracked.count = 2;
+
+        assert!(tracked.take_changed());
+        assert_eq!(tracked.count, 2);
+        assert!(!tracked.take_changed());
+    }
+
+    #[test]
+    fn test_setting_an_equal_value_is_no_change() {
+        let mut tracked = Tracked::new(Counter { count: 1 });
+        tracked.take_changed();
+
+        tracked.set(Counter { count: 1 });
+        assert!(!tracked.take_changed());
+
+        tracked.set(Counter { count: 3 });
+        assert!(tracked.take_changed());
+    }
+
+    #[test]
+    fn test_modify_counts_reported_changes_only() {
+        let mut tracked = Tracked::new(Counter::default());
+        tracked.take_changed();
+
+        assert!(tracked.modify(|counter| counter.increment_below(1)));
+        assert!(tracked.take_changed());
+
+        assert!(!tracked.modify(|counter| counter.increment_below(1)));
+        assert!(!tracked.take_changed());
+    }
+}
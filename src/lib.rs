@@ -0,0 +1,401 @@
+// Copyright (C) Oliver Amann
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 as
+// published by the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+// Without the window frontend, parts of the shared modules go unused
+#![cfg_attr(not(feature = "window"), allow(dead_code))]
+
+#[cfg(feature = "window")]
+use crate::color::Color;
+#[cfg(feature = "window")]
+use crate::daily::DailyChallenge;
+#[cfg(feature = "window")]
+use crate::game_mode::GameMode;
+#[cfg(feature = "window")]
+use crate::screen::Screen;
+#[cfg(feature = "window")]
+use tetra::input::Key;
+#[cfg(feature = "window")]
+use tetra::time::Timestep;
+#[cfg(feature = "window")]
+use tetra::{graphics, Context, ContextBuilder, Event, State as TetraState};
+
+mod achievements;
+#[cfg(feature = "window")]
+mod alert;
+mod assets;
+#[cfg(feature = "window")]
+mod capture;
+#[cfg(feature = "window")]
+mod cli;
+pub mod color;
+mod config;
+mod countdown;
+mod daily;
+#[cfg(feature = "window")]
+mod drawable_collection;
+mod events;
+#[cfg(feature = "window")]
+mod export;
+pub mod game;
+mod game_mode;
+mod high_scores;
+mod i18n;
+mod item;
+#[cfg(feature = "window")]
+mod lazy_drawable;
+#[cfg(feature = "window")]
+mod menu;
+mod play_state;
+#[cfg(feature = "window")]
+mod render;
+#[cfg(feature = "window")]
+pub mod resources;
+mod savegame;
+#[cfg(feature = "window")]
+mod screen;
+mod settings;
+mod statistics;
+mod storage;
+#[cfg(feature = "window")]
+mod toast;
+#[cfg(unix)]
+pub mod tui;
+
+#[cfg(feature = "window")]
+pub enum ScreenRefMut<'a> {
+    Start(&'a mut screen::StartScreen),
+    Game(&'a mut screen::GameScreen),
+    Achievements(&'a mut screen::AchievementsScreen),
+    Statistics(&'a mut screen::StatisticsScreen),
+    Settings(&'a mut screen::SettingsScreen),
+    Leaderboard(&'a mut screen::LeaderboardScreen),
+}
+
+#[cfg(feature = "window")]
+impl ScreenRefMut<'_> {
+    pub fn name(&self) -> ScreenName {
+        match self {
+            ScreenRefMut::Start(_) => ScreenName::Start,
+            ScreenRefMut::Game(_) => ScreenName::Game,
+            ScreenRefMut::Achievements(_) => ScreenName::Achievements,
+            ScreenRefMut::Statistics(_) => ScreenName::Statistics,
+            ScreenRefMut::Settings(_) => ScreenName::Settings,
+            ScreenRefMut::Leaderboard(_) => ScreenName::Leaderboard,
+        }
+    }
+}
+
+#[cfg(feature = "window")]
+#[derive(Eq, PartialEq, Copy, Clone)]
+pub enum ScreenName {
+    Start,
+    Game,
+    Achievements,
+    Statistics,
+    Settings,
+    Leaderboard,
+}
+
+#[cfg(feature = "window")]
+#[derive(Copy, Clone)]
+pub struct CurrentScreen {
+    name: ScreenName,
+    /// Screen that was shown before, which `go_back` returns to
+    previous_name: ScreenName,
+    game_mode: GameMode,
+    resume_saved_game: bool,
+    quit: bool,
+}
+
+#[cfg(feature = "window")]
+impl CurrentScreen {
+    pub fn use_screen(&mut self, screen: ScreenName) {
+        if self.name != screen {
+            self.previous_name = self.name;
+        }
+        self.name = screen;
+    }
+
+    pub fn go_back(&mut self) {
+        self.use_screen(self.previous_name);
+    }
+
+    pub fn name(&self) -> &ScreenName {
+        &self.name
+    }
+
+    /// Selects the mode that the game screen plays
+    pub fn use_game_mode(&mut self, game_mode: GameMode) {
+        self.game_mode = game_mode;
+    }
+
+    pub fn game_mode(&self) -> &GameMode {
+        &self.game_mode
+    }
+
+    /// Lets the game screen continue the saved game when it is entered
+    pub fn request_saved_game(&mut self) {
+        self.resume_saved_game = true;
+    }
+
+    pub fn take_saved_game_request(&mut self) -> bool {
+        std::mem::replace(&mut self.resume_saved_game, false)
+    }
+
+    /// Closes the window after the current event
+    pub fn request_quit(&mut self) {
+        self.quit = true;
+    }
+
+    pub fn take_quit_request(&mut self) -> bool {
+        std::mem::replace(&mut self.quit, false)
+    }
+}
+
+#[cfg(feature = "window")]
+struct Screens {
+    start: screen::StartScreen,
+    game: screen::GameScreen,
+    achievements: screen::AchievementsScreen,
+    statistics: screen::StatisticsScreen,
+    settings: screen::SettingsScreen,
+    leaderboard: screen::LeaderboardScreen,
+}
+
+#[cfg(feature = "window")]
+struct State {
+    current_screen: CurrentScreen,
+    pub screens: Screens,
+    capture: capture::Capture,
+    record_interval: capture::RecordInterval,
+}
+
+#[cfg(feature = "window")]
+impl State {
+    pub fn factory(ctx: &mut Context, options: cli::Options) -> tetra::Result<State> {
+        // Replays start right away
+        let name = if options.replay.is_some() {
+            ScreenName::Game
+        } else {
+            ScreenName::Start
+        };
+
+        let mut capture = capture::Capture::new();
+        if let Some(dir) = options.record {
+            if let Err(err) = capture.start_recording(dir, options.record_interval) {
+                eprintln!("Unable to start recording: {}", err);
+            }
+        }
+
+        let start =
+            screen::StartScreen::try_new(ctx, options.mode, options.settings.level.clone())?;
+        let settings = screen::SettingsScreen::try_new(ctx, options.settings.clone())?;
+        let mut game = screen::GameScreen::try_new(ctx, options.settings, options.replay)?;
+        game.events = events::EventStream::with_log(options.event_log.as_deref());
+
+        Ok(State {
+            current_screen: CurrentScreen {
+                name,
+                previous_name: ScreenName::Start,
+                game_mode: options.mode,
+                resume_saved_game: false,
+                quit: false,
+            },
+            screens: Screens {
+                start,
+                game,
+                achievements: screen::AchievementsScreen::try_new(ctx)?,
+                statistics: screen::StatisticsScreen::try_new(ctx)?,
+                settings,
+                leaderboard: screen::LeaderboardScreen::try_new(ctx)?,
+            },
+            capture,
+            record_interval: options.record_interval,
+        })
+    }
+
+    pub fn current_screen_mut(&mut self) -> &mut dyn Screen {
+        match self.current_screen.name {
+            ScreenName::Start => (&mut self.screens.start) as &mut dyn Screen,
+            ScreenName::Game => (&mut self.screens.game) as &mut dyn Screen,
+            ScreenName::Achievements => (&mut self.screens.achievements) as &mut dyn Screen,
+            ScreenName::Statistics => (&mut self.screens.statistics) as &mut dyn Screen,
+            ScreenName::Settings => (&mut self.screens.settings) as &mut dyn Screen,
+            ScreenName::Leaderboard => (&mut self.screens.leaderboard) as &mut dyn Screen,
+        }
+    }
+
+    /// Lets the current screen know when it has been switched to, and closes
+    /// the window if a screen has asked for it
+    fn enter_changed_screen(&mut self, ctx: &mut Context, previous_screen: ScreenName) {
+        if self.current_screen.take_quit_request() {
+            tetra::window::quit(ctx);
+        }
+
+        if self.current_screen.name == previous_screen {
+            return;
+        }
+
+        // The level or the daily challenge is chosen on the start screen,
+        // unless a saved game is continued with its own
+        if previous_screen == ScreenName::Start && !self.current_screen.resume_saved_game {
+            if self.screens.start.take_daily_request() {
+                let challenge = DailyChallenge::today(self.screens.game.player_settings());
+                self.screens.game.play_daily(challenge);
+            } else {
+                self.screens.game.leave_daily();
+
+                let mut settings = self.screens.game.next_settings().clone();
+                if self.screens.start.apply_level(&mut settings) {
+                    self.screens.game.use_settings(settings);
+                }
+            }
+        }
+        // The settings screen changes the player's settings of the next game
+        if previous_screen == ScreenName::Settings {
+            let settings = self.screens.settings.settings.clone();
+            self.screens.game.use_player_settings(settings);
+        }
+        if self.current_screen.name == ScreenName::Settings {
+            let settings = self.screens.game.player_settings().clone();
+            self.screens.settings.use_settings(settings);
+        }
+        if self.current_screen.name == ScreenName::Leaderboard {
+            let settings = self.screens.game.player_settings().clone();
+            self.screens.leaderboard.use_settings(settings);
+        }
+
+        let mut current_screen = self.current_screen;
+        self.current_screen_mut().enter(&mut current_screen);
+        self.current_screen = current_screen;
+    }
+}
+
+#[cfg(feature = "window")]
+impl Drop for State {
+    /// Keeps the game in progress when the window is closed, also while
+    /// another screen is shown on top of it, e.g. the settings
+    fn drop(&mut self) {
+        self.screens.game.save();
+    }
+}
+
+#[cfg(feature = "window")]
+impl TetraState for State {
+    fn update(&mut self, ctx: &mut Context) -> tetra::Result {
+        let previous_screen = self.current_screen.name;
+        let mut current_screen = self.current_screen;
+        self.current_screen_mut().update(&mut current_screen);
+        self.current_screen = current_screen;
+        self.enter_changed_screen(ctx, previous_screen);
+
+        Ok(())
+    }
+
+    fn draw(&mut self, ctx: &mut Context) -> tetra::Result {
+        graphics::clear(ctx, Color::rgba(0, 0, 0, 1.0).into());
+
+        self.current_screen_mut().draw(ctx)?;
+
+        let tick = self.screens.game.session.game.ticks;
+        self.capture.capture(ctx, tick);
+
+        Ok(())
+    }
+
+    fn event(&mut self, ctx: &mut Context, event: Event) -> tetra::Result {
+        match event {
+            Event::KeyPressed { key: Key::F12 } => self.capture.request_screenshot(),
+            Event::KeyPressed { key: Key::F11 } if self.capture.is_recording() => {
+                self.capture.stop_recording()
+            }
+            Event::KeyPressed { key: Key::F11 } => {
+                if let Err(err) = self.capture.start_new_recording(self.record_interval) {
+                    eprintln!("Unable to start recording: {}", err);
+                }
+            }
+            _ => {}
+        }
+
+        let previous_screen = self.current_screen.name;
+        let mut current_screen = self.current_screen;
+        self.current_screen_mut().event(&mut current_screen, event);
+        self.current_screen = current_screen;
+        self.enter_changed_screen(ctx, previous_screen);
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "window")]
+const PLAYGROUND_WIDTH: u16 = config::TILE_SIZE * config::TILE_COUNT_X;
+#[cfg(feature = "window")]
+const PLAYGROUND_HEIGHT: u16 = config::TILE_SIZE * config::TILE_COUNT_Y;
+#[cfg(feature = "window")]
+const WINDOW_WIDTH: u16 = PLAYGROUND_WIDTH + config::PLAYGROUND_WALL_WIDTH * 2;
+#[cfg(feature = "window")]
+const WINDOW_HEIGHT: u16 =
+    PLAYGROUND_HEIGHT + config::PLAYGROUND_WALL_WIDTH * 2 + config::HUD_HEIGHT;
+
+#[cfg(feature = "window")]
+pub fn run() -> tetra::Result {
+    let options = match cli::parse(std::env::args().skip(1)) {
+        Ok(cli::Command::Play(options)) => options,
+        Ok(cli::Command::ExportGif {
+            replay,
+            output,
+            tile_size,
+        }) => {
+            match export::export_gif(&replay, &output, tile_size) {
+                Ok(frames) => println!("Exported {} frames to '{}'", frames, output.display()),
+                Err(err) => {
+                    eprintln!("error: unable to export '{}': {}", output.display(), err);
+                    std::process::exit(1);
+                }
+            }
+            return Ok(());
+        }
+        Ok(cli::Command::Help) => {
+            print!("{}", cli::USAGE);
+            return Ok(());
+        }
+        Err(err) => {
+            eprintln!(
+                "error: {}\n\nRun 'snake --help' for the available options.",
+                err
+            );
+            std::process::exit(2);
+        }
+    };
+    i18n::init(&options.settings.language);
+
+    if options.headless {
+        if let Some(replay) = &options.replay {
+            let mut events = events::EventStream::with_log(options.event_log.as_deref());
+            cli::run_headless(replay, &mut events);
+        }
+        return Ok(());
+    }
+
+    ContextBuilder::new(
+        i18n::tr("app-title"),
+        WINDOW_WIDTH as i32,
+        WINDOW_HEIGHT as i32,
+    )
+    .timestep(Timestep::Fixed(config::UPDATES_PER_SECOND))
+    .show_mouse(true)
+    .fullscreen(options.fullscreen)
+    .build()?
+    .run(|ctx| State::factory(ctx, *options))
+}
@@ -0,0 +1,423 @@
+use crate::alert::Alert;
+use crate::assets;
+use crate::config;
+use crate::i18n;
+use crate::lazy_drawable::Tracked;
+use crate::resources;
+use crate::screen::Drawable;
+use crate::{WINDOW_HEIGHT, WINDOW_WIDTH};
+use tetra::input::{GamepadButton, Key, MouseButton};
+use tetra::math::Vec2;
+use tetra::{Context, Event};
+
+const TITLE_Y: f32 = 60.0;
+const FIRST_ITEM_Y: f32 = 150.0;
+const ITEM_SPACING: f32 = 42.0;
+const ITEM_WIDTH: f32 = 360.0;
+const ITEM_HEIGHT: f32 = 34.0;
+const ITEM_FONT_SIZE: f32 = 22.0;
+const HINT_Y: f32 = (WINDOW_HEIGHT - 40) as f32;
+/// Space that is kept free between the last item and the hint
+const HINT_MARGIN: f32 = 8.0;
+
+/// What the player does with a menu, independent of the input device
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum MenuInput {
+    Previous,
+    Next,
+    /// Lowers the value of the selected item, e.g. a setting
+    Decrease,
+    Increase,
+    Select,
+    Back,
+    /// The mouse has moved to the position
+    Point(Vec2<f32>),
+    Click,
+}
+
+impl MenuInput {
+    /// Maps keys, mouse and gamepad buttons to menu inputs
+    pub fn from_event(event: &Event) -> Option<MenuInput> {
+        match event {
+            Event::KeyPressed {
+                key: Key::Up | Key::W,
+            }
+            | Event::GamepadButtonPressed {
+                button: GamepadButton::Up,
+                ..
+            } => Some(MenuInput::Previous),
+            Event::KeyPressed {
+                key: Key::Down | Key::S,
+            }
+            | Event::GamepadButtonPressed {
+                button: GamepadButton::Down,
+                ..
+            } => Some(MenuInput::Next),
+            Event::KeyPressed {
+                key: Key::Left | Key::A,
+            }
+            | Event::GamepadButtonPressed {
+                button: GamepadButton::Left,
+                ..
+            } => Some(MenuInput::Decrease),
+            Event::KeyPressed {
+                key: Key::Right | Key::D,
+            }
+            | Event::GamepadButtonPressed {
+                button: GamepadButton::Right,
+                ..
+            } => Some(MenuInput::Increase),
+            Event::KeyPressed {
+                key: Key::Enter | Key::NumPadEnter | Key::Space,
+            }
+            | Event::GamepadButtonPressed {
+                button: GamepadButton::A,
+                ..
+            } => Some(MenuInput::Select),
+            Event::KeyPressed {
+                key: Key::Escape | Key::Backspace,
+            }
+            | Event::GamepadButtonPressed {
+                button: GamepadButton::B | GamepadButton::Back,
+                ..
+            } => Some(MenuInput::Back),
+            Event::MouseMoved { position } => Some(MenuInput::Point(*position)),
+            Event::MouseButtonPressed {
+                button: MouseButton::Left,
+            } => Some(MenuInput::Click),
+            _ => None,
+        }
+    }
+}
+
+/// Result of an input that the owner of the menu acts on
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum MenuChoice<T> {
+    Select(T),
+    /// Changes the value of an item by the step, which is -1 or 1
+    Adjust(T, i32),
+    Back,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct MenuItem<T> {
+    pub action: T,
+    pub label: String,
+}
+
+/// Vertical list of items of which one is selected, which can be used with
+/// the keyboard, the mouse and gamepads
+#[derive(Clone, Debug, PartialEq)]
+pub struct Menu<T> {
+    pub title: String,
+    pub hint: Option<String>,
+    items: Vec<MenuItem<T>>,
+    selected_index: usize,
+    /// Last position of the mouse, which clicks refer to
+    pointer: Option<Vec2<f32>>,
+}
+
+impl<T: Copy + PartialEq> Menu<T> {
+    pub fn new<S: Into<String>>(title: S, items: Vec<(T, String)>) -> Menu<T> {
+        Menu {
+            title: title.into(),
+            hint: None,
+            items: items
+                .into_iter()
+                .map(|(action, label)| MenuItem { action, label })
+                .collect(),
+            selected_index: 0,
+            pointer: None,
+        }
+    }
+
+    pub fn items(&self) -> &[MenuItem<T>] {
+        &self.items
+    }
+
+    pub fn selected(&self) -> Option<T> {
+        self.items.get(self.selected_index).map(|item| item.action)
+    }
+
+    pub fn select(&mut self, action: T) {
+        if let Some(index) = self.items.iter().position(|item| item.action == action) {
+            self.selected_index = index;
+        }
+    }
+
+    pub fn set_label<S: Into<String>>(&mut self, action: T, label: S) {
+        if let Some(item) = self.items.iter_mut().find(|item| item.action == action) {
+            item.label = label.into();
+        }
+    }
+
+    /// Position of the first item and the spacing of the items, which are
+    /// moved closer together and further up when there are too many of them
+    /// to fit above the hint
+    fn layout(&self) -> (f32, f32) {
+        let bottom = HINT_Y - HINT_MARGIN;
+        let gaps = self.items.len().saturating_sub(1).max(1) as f32;
+        let spacing =
+            ((bottom - FIRST_ITEM_Y - ITEM_HEIGHT) / gaps).clamp(ITEM_HEIGHT, ITEM_SPACING);
+        let first_y = FIRST_ITEM_Y.min(bottom - ITEM_HEIGHT - gaps * spacing);
+
+        (first_y, spacing)
+    }
+
+    /// Position and size of an item in the window
+    fn item_rect(&self, index: usize) -> (Vec2<f32>, Vec2<f32>) {
+        let (first_y, spacing) = self.layout();
+
+        (
+            Vec2::new(
+                (WINDOW_WIDTH as f32 - ITEM_WIDTH) / 2.0,
+                first_y + index as f32 * spacing,
+            ),
+            Vec2::new(ITEM_WIDTH, ITEM_HEIGHT),
+        )
+    }
+
+    fn item_at(&self, point: Vec2<f32>) -> Option<usize> {
+        (0..self.items.len()).find(|index| {
+            let (position, size) = self.item_rect(*index);
+
+            point.x >= position.x
+                && point.x < position.x + size.x
+                && point.y >= position.y
+                && point.y < position.y + size.y
+        })
+    }
+
+    /// Moves the selection or returns the choice that the input makes
+    pub fn handle(&mut self, input: MenuInput) -> Option<MenuChoice<T>> {
+        if self.items.is_empty() {
+            return match input {
+                MenuInput::Back => Some(MenuChoice::Back),
+                _ => None,
+            };
+        }
+
+        let count = self.items.len();
+        match input {
+            MenuInput::Previous => {
+                self.selected_index = (self.selected_index + count - 1) % count;
+                None
+            }
+            MenuInput::Next => {
+                self.selected_index = (self.selected_index + 1) % count;
+                None
+            }
+            MenuInput::Decrease => self.selected().map(|action| MenuChoice::Adjust(action, -1)),
+            MenuInput::Increase => self.selected().map(|action| MenuChoice::Adjust(action, 1)),
+            MenuInput::Select => self.selected().map(MenuChoice::Select),
+            MenuInput::Back => Some(MenuChoice::Back),
+            MenuInput::Point(point) => {
+                self.pointer = Some(point);
+                if let Some(index) = self.item_at(point) {
+                    self.selected_index = index;
+                }
+                None
+            }
+            MenuInput::Click => {
+                let index = self.pointer.and_then(|point| self.item_at(point))?;
+                self.selected_index = index;
+                Some(MenuChoice::Select(self.items[index].action))
+            }
+        }
+    }
+}
+
+impl<T: Copy + PartialEq> Tracked<Menu<T>> {
+    /// Passes the input on to the menu, which only counts as changed when
+    /// its selection has changed, not e.g. whenever the mouse moves
+    pub fn handle_input(&mut self, input: MenuInput) -> Option<MenuChoice<T>> {
+        let mut choice = None;
+        self.modify(|menu| {
+            let selected_index = menu.selected_index;
+            choice = menu.handle(input);
+            menu.selected_index != selected_index
+        });
+
+        choice
+    }
+}
+
+impl Menu<bool> {
+    /// Asks whether to go ahead with something that can't be undone, which
+    /// is declined unless the player confirms it
+    pub fn confirm<Q: Into<String>, C: Into<String>>(question: Q, consequence: C) -> Menu<bool> {
+        let mut menu = Menu::new(
+            question,
+            vec![(false, i18n::tr("no")), (true, i18n::tr("yes"))],
+        );
+        menu.hint = Some(consequence.into());
+
+        menu
+    }
+}
+
+impl<T: Copy + PartialEq> Drawable for Menu<T> {
+    fn draw(&mut self, ctx: &mut Context) -> tetra::Result {
+        resources::draw_rectangle(
+            ctx,
+            Vec2::zero(),
+            Vec2::new(WINDOW_WIDTH as f32, WINDOW_HEIGHT as f32),
+            &config::ALERT_BACKGROUND_COLOR,
+        )?;
+
+        let title_font = assets::font(ctx, 48.0)?;
+        Alert::draw_text(ctx, &self.title, title_font, TITLE_Y)?;
+
+        let item_font = assets::font(ctx, ITEM_FONT_SIZE)?;
+        for (index, item) in self.items.iter().enumerate() {
+            let (position, size) = self.item_rect(index);
+            if index == self.selected_index {
+                resources::draw_rectangle(ctx, position, size, &config::MENU_SELECTION_COLOR)?;
+            }
+
+            let text_y = position.y + (ITEM_HEIGHT - ITEM_FONT_SIZE) / 2.0 - 2.0;
+            Alert::draw_text(ctx, &item.label, item_font.clone(), text_y)?;
+        }
+
+        if let Some(hint) = &self.hint {
+            let hint_font = assets::font(ctx, 16.0)?;
+            Alert::draw_text(ctx, hint, hint_font, HINT_Y)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn menu() -> Menu<char> {
+        Menu::new(
+            "Menu",
+            vec![
+                ('a', "A".to_string()),
+                ('b', "B".to_string()),
+                ('c', "C".to_string()),
+            ],
+        )
+    }
+
+    fn center_of(menu: &Menu<char>, index: usize) -> Vec2<f32> {
+        let (position, size) = menu.item_rect(index);
+        position + size / 2.0
+    }
+
+    #[test]
+    fn test_navigation_wraps_around() {
+        let mut menu = menu();
+
+        assert_eq!(menu.handle(MenuInput::Previous), None);
+        assert_eq!(menu.selected(), Some('c'));
+        assert_eq!(menu.handle(MenuInput::Next), None);
+        assert_eq!(menu.selected(), Some('a'));
+        assert_eq!(
+            menu.handle(MenuInput::Select),
+            Some(MenuChoice::Select('a'))
+        );
+        assert_eq!(
+            menu.handle(MenuInput::Increase),
+            Some(MenuChoice::Adjust('a', 1))
+        );
+        assert_eq!(menu.handle(MenuInput::Back), Some(MenuChoice::Back));
+    }
+
+    #[test]
+    fn test_mouse_selects_items() {
+        let mut menu = menu();
+
+        // Clicks before the mouse has moved don't hit anything
+        assert_eq!(menu.handle(MenuInput::Click), None);
+
+        assert_eq!(menu.handle(MenuInput::Point(center_of(&menu, 2))), None);
+        assert_eq!(menu.selected(), Some('c'));
+        assert_eq!(menu.handle(MenuInput::Click), Some(MenuChoice::Select('c')));
+
+        // Moving away keeps the selection, but clicks miss
+        assert_eq!(menu.handle(MenuInput::Point(Vec2::zero())), None);
+        assert_eq!(menu.selected(), Some('c'));
+        assert_eq!(menu.handle(MenuInput::Click), None);
+    }
+
+    #[test]
+    fn test_unchanged_selection_is_not_redrawn() {
+        // Lazy drawables redraw menus only when their tracked value changed
+        let mut menu = Tracked::new(menu());
+        menu.take_changed();
+
+        let point = center_of(&menu, 1);
+        assert_eq!(menu.handle_input(MenuInput::Point(point)), None);
+        assert!(menu.take_changed());
+
+        for offset in 1..5 {
+            let point = point + Vec2::new(offset as f32, 0.0);
+            assert_eq!(menu.handle_input(MenuInput::Point(point)), None);
+        }
+        assert_eq!(
+            menu.handle_input(MenuInput::Select),
+            Some(MenuChoice::Select('b'))
+        );
+        assert!(!menu.take_changed());
+
+        menu.handle_input(MenuInput::Next);
+        assert!(menu.take_changed());
+    }
+
+    #[test]
+    fn test_items_stay_above_the_hint() {
+        for count in 1..=12 {
+            let items = (0..count).map(|index| (index, index.to_string())).collect();
+            let mut menu = Menu::new("Menu", items);
+            menu.hint = Some("Hint".to_string());
+
+            for index in 0..count {
+                let (position, size) = menu.item_rect(index);
+                assert!(position.y > TITLE_Y, "{} of {}", index, count);
+                assert!(position.y + size.y <= HINT_Y, "{} of {}", index, count);
+            }
+        }
+
+        // Menus with few items keep the usual spacing
+        let menu = menu();
+        assert_eq!(menu.item_rect(1).0.y - menu.item_rect(0).0.y, ITEM_SPACING);
+    }
+
+    #[test]
+    fn test_confirm_defaults_to_no() {
+        let mut menu = Menu::confirm("Sure?", "Nothing happens");
+
+        assert_eq!(
+            menu.handle(MenuInput::Select),
+            Some(MenuChoice::Select(false))
+        );
+        menu.handle(MenuInput::Next);
+        assert_eq!(
+            menu.handle(MenuInput::Select),
+            Some(MenuChoice::Select(true))
+        );
+    }
+
+    #[test]
+    fn test_inputs_of_events() {
+        assert_eq!(
+            MenuInput::from_event(&Event::KeyPressed { key: Key::Down }),
+            Some(MenuInput::Next)
+        );
+        assert_eq!(
+            MenuInput::from_event(&Event::GamepadButtonPressed {
+                id: 0,
+                button: GamepadButton::A
+            }),
+            Some(MenuInput::Select)
+        );
+        assert_eq!(
+            MenuInput::from_event(&Event::KeyReleased { key: Key::Down }),
+            None
+        );
+    }
+}
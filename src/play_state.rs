@@ -0,0 +1,214 @@
+use std::fmt;
+
+/// State of the game screen, which decides what is drawn and which inputs
+/// are handled
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PlayState {
+    /// A new game that hasn't been updated yet
+    Ready,
+    /// Counting down before the game starts or continues
+    Countdown,
+    Playing,
+    Paused,
+    GameOver,
+    Victory,
+}
+
+/// Transition that isn't allowed, e.g. pausing a finished game
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct InvalidTransition {
+    pub from: PlayState,
+    pub to: PlayState,
+}
+
+impl PlayState {
+    pub const ALL: [PlayState; 6] = [
+        PlayState::Ready,
+        PlayState::Countdown,
+        PlayState::Playing,
+        PlayState::Paused,
+        PlayState::GameOver,
+        PlayState::Victory,
+    ];
+
+    pub fn can_transition_to(&self, next: PlayState) -> bool {
+        use PlayState::*;
+
+        match (self, next) {
+            // Every game can be started over
+            (from, Ready) => *from != Ready,
+            (Ready, Countdown | Playing | Paused) => true,
+            (Countdown, Playing | Paused) => true,
+            (Playing, Paused | GameOver | Victory) => true,
+            (Paused, Countdown | Playing) => true,
+            // Crashes in practice can be rewound
+            (GameOver, Playing) => true,
+            _ => false,
+        }
+    }
+
+    pub fn transition_to(&self, next: PlayState) -> Result<PlayState, InvalidTransition> {
+        if self.can_transition_to(next) {
+            Ok(next)
+        } else {
+            Err(InvalidTransition {
+                from: *self,
+                to: next,
+            })
+        }
+    }
+
+    /// Whether the game is over, with either outcome
+    pub fn is_finished(&self) -> bool {
+        matches!(self, PlayState::GameOver | PlayState::Victory)
+    }
+}
+
+/// Something that is in one of the states, with hooks that run when a state
+/// is left and entered
+pub trait StateMachine {
+    fn state(&self) -> PlayState;
+
+    fn set_state(&mut self, state: PlayState);
+
+    fn exit_state(&mut self, _state: PlayState, _next: PlayState) {}
+
+    fn enter_state(&mut self, _state: PlayState, _previous: PlayState) {}
+
+    /// Leaves the current state for the next one if that is allowed
+    fn transition(&mut self, next: PlayState) -> Result<(), InvalidTransition> {
+        let previous = self.state();
+        previous.transition_to(next)?;
+
+        self.exit_state(previous, next);
+        self.set_state(next);
+        self.enter_state(next, previous);
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for InvalidTransition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "invalid transition from {:?} to {:?}",
+            self.from, self.to
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use PlayState::*;
+
+    #[test]
+    fn test_transitions() {
+        let allowed = [
+            (Ready, Countdown),
+            (Ready, Playing),
+            (Ready, Paused),
+            (Countdown, Ready),
+            (Countdown, Playing),
+            (Countdown, Paused),
+            (Playing, Ready),
+            (Playing, Paused),
+            (Playing, GameOver),
+            (Playing, Victory),
+            (Paused, Ready),
+            (Paused, Countdown),
+            (Paused, Playing),
+            (GameOver, Ready),
+            (GameOver, Playing),
+            (Victory, Ready),
+        ];
+
+        for from in PlayState::ALL.iter() {
+            for to in PlayState::ALL.iter() {
+                let is_allowed = allowed.contains(&(*from, *to));
+
+                assert_eq!(
+                    from.can_transition_to(*to),
+                    is_allowed,
+                    "{:?} to {:?}",
+                    from,
+                    to
+                );
+                assert_eq!(
+                    from.transition_to(*to),
+                    if is_allowed {
+                        Ok(*to)
+                    } else {
+                        Err(InvalidTransition {
+                            from: *from,
+                            to: *to,
+                        })
+                    }
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_finished_states() {
+        let finished: Vec<PlayState> = PlayState::ALL
+            .iter()
+            .copied()
+            .filter(PlayState::is_finished)
+            .collect();
+
+        assert_eq!(finished, vec![GameOver, Victory]);
+    }
+
+    #[derive(Default)]
+    struct Recorder {
+        state: Option<PlayState>,
+        hooks: Vec<String>,
+    }
+
+    impl StateMachine for Recorder {
+        fn state(&self) -> PlayState {
+            self.state.unwrap_or(Ready)
+        }
+
+        fn set_state(&mut self, state: PlayState) {
+            self.state = Some(state);
+        }
+
+        fn exit_state(&mut self, state: PlayState, next: PlayState) {
+            self.hooks.push(format!("exit {:?} for {:?}", state, next));
+        }
+
+        fn enter_state(&mut self, state: PlayState, previous: PlayState) {
+            self.hooks
+                .push(format!("enter {:?} from {:?}", state, previous));
+        }
+    }
+
+    #[test]
+    fn test_transition_runs_hooks() {
+        let mut recorder = Recorder::default();
+
+        assert_eq!(recorder.transition(Playing), Ok(()));
+        assert_eq!(recorder.transition(Victory), Ok(()));
+        assert_eq!(
+            recorder.transition(Paused),
+            Err(InvalidTransition {
+                from: Victory,
+                to: Paused,
+            })
+        );
+
+        assert_eq!(recorder.state(), Victory);
+        assert_eq!(
+            recorder.hooks,
+            vec![
+                "exit Ready for Playing",
+                "enter Playing from Ready",
+                "exit Playing for Victory",
+                "enter Victory from Playing",
+            ]
+        );
+    }
+}
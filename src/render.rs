@@ -0,0 +1,357 @@
+//! Geometry of the board and a renderer that draws it on the CPU, for exports
+//! and tests that run without a window
+
+use crate::color::Color;
+use crate::config;
+use crate::game::Game;
+use crate::settings::Settings;
+#[cfg(test)]
+use crate::WINDOW_WIDTH;
+use crate::{PLAYGROUND_HEIGHT, PLAYGROUND_WIDTH};
+use tetra::math::Vec2;
+
+/// Size and position of the tiles on the playground, so that boards of any
+/// size fit into the window
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct BoardLayout {
+    pub tile_size: f32,
+    /// Distance of the board to the edges of the playground
+    pub offset: Vec2<f32>,
+    pub width: u16,
+    pub height: u16,
+}
+
+impl BoardLayout {
+    pub fn fit(width: u16, height: u16) -> BoardLayout {
+        let tile_size = (PLAYGROUND_WIDTH as f32 / width as f32)
+            .min(PLAYGROUND_HEIGHT as f32 / height as f32)
+            .floor();
+
+        BoardLayout {
+            tile_size,
+            offset: Vec2::new(
+                ((PLAYGROUND_WIDTH as f32 - width as f32 * tile_size) / 2.0).floor(),
+                ((PLAYGROUND_HEIGHT as f32 - height as f32 * tile_size) / 2.0).floor(),
+            ),
+            width,
+            height,
+        }
+    }
+
+    /// Layout of a playground that is exactly as large as the board
+    pub fn with_tile_size(width: u16, height: u16, tile_size: u32) -> BoardLayout {
+        BoardLayout {
+            tile_size: tile_size as f32,
+            offset: Vec2::zero(),
+            width,
+            height,
+        }
+    }
+
+    /// Top left corner of a tile on the playground
+    pub fn tile_position(&self, position: Vec2<i32>) -> Vec2<f32> {
+        self.offset + position.as_() * self.tile_size
+    }
+
+    pub fn board_size(&self) -> Vec2<f32> {
+        Vec2::new(self.width as f32, self.height as f32) * self.tile_size
+    }
+
+    /// Position and size of the ground on the playground
+    pub fn ground_rect(&self) -> (Vec2<f32>, Vec2<f32>) {
+        (self.offset, self.board_size())
+    }
+
+    /// Position and size of a wall of a level, which fills its whole tile
+    pub fn wall_rect(&self, position: Vec2<i32>) -> (Vec2<f32>, Vec2<f32>) {
+        (
+            self.tile_position(position),
+            Vec2::broadcast(self.tile_size),
+        )
+    }
+
+    /// Position and size of the snake or an item on a tile, which leave a
+    /// margin to their neighbours
+    pub fn tile_rect(&self, position: Vec2<i32>) -> (Vec2<f32>, Vec2<f32>) {
+        let size = self.tile_size * config::TILE_SCALE;
+        let margin = (self.tile_size - size) / 2.0;
+
+        (
+            self.tile_position(position) + Vec2::broadcast(margin),
+            Vec2::broadcast(size),
+        )
+    }
+}
+
+/// Image in RGBA, from the top left to the bottom right
+#[derive(Clone, PartialEq, Debug)]
+pub struct Image {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+impl Image {
+    pub fn new(width: u32, height: u32, color: &Color) -> Image {
+        let pixel = [color.r, color.g, color.b, (color.a * 255.0).round() as u8];
+
+        Image {
+            width,
+            height,
+            pixels: pixel.repeat(width as usize * height as usize),
+        }
+    }
+
+    /// Fills the pixels whose centers are inside of the rectangle, like
+    /// OpenGL does, blending translucent colors over the image
+    pub fn fill_rect(&mut self, position: Vec2<f32>, size: Vec2<f32>, color: &Color) {
+        let first = |start: f32| (start - 0.5).ceil().max(0.0) as u32;
+        let (left, top) = (first(position.x), first(position.y));
+        let right = first(position.x + size.x).min(self.width);
+        let bottom = first(position.y + size.y).min(self.height);
+
+        for y in top..bottom {
+            for x in left..right {
+                let index = (y * self.width + x) as usize * 4;
+                let pixel = &mut self.pixels[index..index + 4];
+                let blend = |source: u8, target: u8| {
+                    (source as f32 * color.a + target as f32 * (1.0 - color.a)).round() as u8
+                };
+
+                pixel[0] = blend(color.r, pixel[0]);
+                pixel[1] = blend(color.g, pixel[1]);
+                pixel[2] = blend(color.b, pixel[2]);
+                pixel[3] = blend(255, pixel[3]);
+            }
+        }
+    }
+}
+
+/// Draws the board of a game with the geometry and colors of `Background`
+/// and `Tile`, surrounded by the wall
+#[derive(Clone, Copy, Debug)]
+pub struct BoardRenderer {
+    pub layout: BoardLayout,
+    /// Width of the wall around the playground
+    pub wall_width: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl BoardRenderer {
+    /// Renders the board like the window shows it, without the HUD below, so
+    /// that tests can check what players see
+    #[cfg(test)]
+    pub fn window(settings: &Settings) -> BoardRenderer {
+        let wall_width = config::PLAYGROUND_WALL_WIDTH as u32;
+
+        BoardRenderer {
+            layout: BoardLayout::fit(settings.board_width, settings.board_height),
+            wall_width,
+            width: WINDOW_WIDTH as u32,
+            height: PLAYGROUND_HEIGHT as u32 + wall_width * 2,
+        }
+    }
+
+    /// Renders the board at any tile size, e.g. for small exports
+    pub fn with_tile_size(settings: &Settings, tile_size: u32) -> BoardRenderer {
+        let layout =
+            BoardLayout::with_tile_size(settings.board_width, settings.board_height, tile_size);
+        let wall_width = (tile_size / 10).max(1);
+        let board_size = layout.board_size();
+
+        BoardRenderer {
+            layout,
+            wall_width,
+            width: board_size.x as u32 + wall_width * 2,
+            height: board_size.y as u32 + wall_width * 2,
+        }
+    }
+
+    pub fn render(&self, game: &Game) -> Image {
+        let mut image = Image::new(self.width, self.height, &config::PLAYGROUND_WALL_COLOR);
+        let playground_position = Vec2::broadcast(self.wall_width as f32);
+        let mut fill = |(position, size): (Vec2<f32>, Vec2<f32>), color: &Color| {
+            image.fill_rect(playground_position + position, size, color)
+        };
+
+        fill(self.layout.ground_rect(), &config::PLAYGROUND_GROUND_COLOR);
+        for wall in game.settings.walls() {
+            fill(self.layout.wall_rect(*wall), &config::PLAYGROUND_WALL_COLOR);
+        }
+
+        for position in game.snake.tail.iter() {
+            fill(self.layout.tile_rect(*position), &config::SNAKE_TAIL_COLOR);
+        }
+        fill(
+            self.layout.tile_rect(game.snake.head),
+            &config::SNAKE_HEAD_COLOR,
+        );
+        for apple in game.apples.iter() {
+            fill(self.layout.tile_rect(apple.position), &apple.kind.color());
+        }
+
+        image
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{Direction, Item, Level};
+    use crate::game_mode::GameMode;
+    use crate::item::ItemKind;
+    use std::path::PathBuf;
+
+    fn pixel(image: &Image, x: u32, y: u32) -> [u8; 4] {
+        let index = (y * image.width + x) as usize * 4;
+        let mut pixel = [0; 4];
+        pixel.copy_from_slice(&image.pixels[index..index + 4]);
+
+        pixel
+    }
+
+    /// Compares the image with the committed PNG of the same name. Run the
+    /// tests with `UPDATE_GOLDEN=1` to accept intended changes.
+    fn assert_golden(name: &str, image: &Image) {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests")
+            .join("golden")
+            .join(format!("{}.png", name));
+        let save = |path: &PathBuf| {
+            image::save_buffer(
+                path,
+                &image.pixels,
+                image.width,
+                image.height,
+                image::ColorType::Rgba8,
+            )
+            .unwrap()
+        };
+
+        if std::env::var_os("UPDATE_GOLDEN").is_some() {
+            save(&path);
+            return;
+        }
+
+        let expected = image::open(&path)
+            .unwrap_or_else(|err| panic!("unable to open '{}': {}", path.display(), err))
+            .to_rgba();
+        let dimensions = expected.dimensions();
+        let expected = expected.into_raw();
+        let differences = if dimensions == (image.width, image.height) {
+            expected
+                .chunks_exact(4)
+                .zip(image.pixels.chunks_exact(4))
+                .filter(|(expected, actual)| expected != actual)
+                .count()
+        } else {
+            image.pixels.len() / 4
+        };
+
+        if differences > 0 {
+            let actual_path = std::env::temp_dir().join(format!("snake-golden-{}.png", name));
+            save(&actual_path);
+            panic!(
+                "{} pixels differ from '{}', the rendered image is at '{}'",
+                differences,
+                path.display(),
+                actual_path.display()
+            );
+        }
+    }
+
+    fn game_with(settings: Settings, head: Vec2<i32>, tail: &[(i32, i32)]) -> Game {
+        let mut game = Game::new(GameMode::Endless, settings);
+        game.snake.head = head;
+        game.snake.tail = tail.iter().map(|(x, y)| Vec2::new(*x, *y)).collect();
+        game.snake.direction = Direction::Up;
+        game.apples.clear();
+
+        game
+    }
+
+    #[test]
+    fn test_fill_rect_covers_pixel_centers() {
+        let mut image = Image::new(4, 4, &Color::rgb(0, 0, 0));
+        image.fill_rect(
+            Vec2::new(0.6, 1.0),
+            Vec2::new(2.0, 1.4),
+            &Color::rgb(255, 0, 0),
+        );
+
+        assert_eq!(pixel(&image, 0, 1), [0, 0, 0, 255]);
+        assert_eq!(pixel(&image, 1, 1), [255, 0, 0, 255]);
+        assert_eq!(pixel(&image, 2, 1), [255, 0, 0, 255]);
+        assert_eq!(pixel(&image, 3, 1), [0, 0, 0, 255]);
+        assert_eq!(pixel(&image, 1, 2), [0, 0, 0, 255]);
+    }
+
+    #[test]
+    fn test_tile_rect_leaves_margin() {
+        let layout = BoardLayout::fit(config::TILE_COUNT_X, config::TILE_COUNT_Y);
+        assert_eq!(layout.tile_size, config::TILE_SIZE as f32);
+        assert_eq!(layout.offset, Vec2::zero());
+
+        let (position, size) = layout.tile_rect(Vec2::new(2, 1));
+        assert_eq!(position, Vec2::new(102.5, 52.5));
+        assert_eq!(size, Vec2::broadcast(45.0));
+    }
+
+    #[test]
+    fn test_render_default_board() {
+        let mut game = game_with(Settings::default(), Vec2::new(7, 4), &[(7, 5), (7, 6)]);
+        game.apples = vec![
+            Item::new(ItemKind::Apple, Vec2::new(3, 2), 0),
+            Item::new(ItemKind::GoldenApple, Vec2::new(11, 6), 0),
+        ];
+
+        assert_golden(
+            "default-board",
+            &BoardRenderer::window(&game.settings).render(&game),
+        );
+    }
+
+    #[test]
+    fn test_render_level() {
+        let level = Level::parse(
+            "golden",
+            "\
+####################
+#..................#
+#..................#
+#.....######.......#
+#..................#
+#..S...............#
+#..................#
+#..........#.......#
+#..........#.......#
+#..................#
+#..................#
+####################",
+        )
+        .unwrap();
+        let mut settings = Settings::default();
+        settings.use_level(level);
+        let mut game = game_with(settings, Vec2::new(3, 5), &[(3, 6), (4, 6), (5, 6)]);
+        game.apples = vec![
+            Item::new(ItemKind::Poison, Vec2::new(14, 2), 0),
+            Item::new(ItemKind::Ghost, Vec2::new(8, 9), 0),
+        ];
+
+        let renderer = BoardRenderer::window(&game.settings);
+        // The board doesn't fill the playground, so it is centered
+        assert_ne!(renderer.layout.offset, Vec2::zero());
+        assert_golden("level", &renderer.render(&game));
+    }
+
+    #[test]
+    fn test_render_with_tile_size() {
+        let mut game = game_with(Settings::default(), Vec2::new(0, 0), &[(1, 0), (2, 0)]);
+        game.apples = vec![Item::new(ItemKind::Apple, Vec2::new(14, 8), 0)];
+
+        let renderer = BoardRenderer::with_tile_size(&game.settings, 10);
+        assert_eq!((renderer.width, renderer.height), (152, 92));
+        assert_golden("small-tiles", &renderer.render(&game));
+    }
+}
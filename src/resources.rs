@@ -0,0 +1,49 @@
+//! Graphics resources that are shared by all drawables
+
+use crate::color::Color;
+use std::cell::RefCell;
+use tetra::graphics::{self, DrawParams, Texture};
+use tetra::math::Vec2;
+use tetra::Context;
+
+thread_local! {
+    static WHITE_PIXEL: RefCell<Option<Texture>> = const { RefCell::new(None) };
+}
+
+/// White 1x1 texture that is scaled and tinted to draw rectangles.
+///
+/// It is created on first use and shared afterwards. As consecutive draws of the same texture
+/// are batched by tetra, a whole board of tiles only needs a few draw calls.
+pub fn white_pixel(ctx: &mut Context) -> tetra::Result<Texture> {
+    WHITE_PIXEL.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        if let Some(texture) = &*cache {
+            return Ok(texture.clone());
+        }
+
+        let texture = Texture::from_rgba(ctx, 1, 1, &[255, 255, 255, 255])?;
+        *cache = Some(texture.clone());
+
+        Ok(texture)
+    })
+}
+
+pub fn draw_rectangle(
+    ctx: &mut Context,
+    position: Vec2<f32>,
+    size: Vec2<f32>,
+    color: &Color,
+) -> tetra::Result {
+    let rectangle = white_pixel(ctx)?;
+
+    graphics::draw(
+        ctx,
+        &rectangle,
+        DrawParams::new()
+            .scale(size)
+            .position(position)
+            .color(color.as_tetra()),
+    );
+
+    Ok(())
+}
@@ -0,0 +1,156 @@
+use crate::game::GameSession;
+use crate::play_state::PlayState;
+use crate::storage;
+use serde::{Deserialize, Serialize};
+use std::{error, fmt, io};
+
+/// Version of the save file format, increased whenever the serialized game
+/// state changes so that older saves are rejected instead of misread
+pub const VERSION: u32 = 4;
+
+const FILE_NAME: &str = "savegame.json";
+
+/// A game in progress that can be continued later
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SaveGame {
+    pub version: u32,
+    pub session: GameSession,
+    pub is_paused: bool,
+}
+
+#[derive(Debug)]
+pub enum LoadError {
+    Missing,
+    Io(io::Error),
+    Invalid(serde_json::Error),
+    UnsupportedVersion(Option<u64>),
+}
+
+impl SaveGame {
+    pub fn new(session: GameSession, is_paused: bool) -> SaveGame {
+        SaveGame {
+            version: VERSION,
+            session,
+            is_paused,
+        }
+    }
+
+    pub fn from_json(content: &str) -> Result<SaveGame, LoadError> {
+        // The version is checked first, as the rest of an old save may not
+        // match the current format anymore
+        let value: serde_json::Value = serde_json::from_str(content).map_err(LoadError::Invalid)?;
+        let version = value.get("version").and_then(|version| version.as_u64());
+        if version != Some(VERSION as u64) {
+            return Err(LoadError::UnsupportedVersion(version));
+        }
+
+        serde_json::from_value(value).map_err(LoadError::Invalid)
+    }
+
+    pub fn load() -> Result<SaveGame, LoadError> {
+        match storage::read(&storage::data_dir(), FILE_NAME) {
+            Ok(content) => SaveGame::from_json(&content),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Err(LoadError::Missing),
+            Err(err) => Err(LoadError::Io(err)),
+        }
+    }
+
+    pub fn save(&self) {
+        if let Err(err) = storage::save(&storage::data_dir(), FILE_NAME, self) {
+            eprintln!("Unable to save the game: {}", err);
+        }
+    }
+
+    /// Saves the game in progress, so that it can be continued later.
+    /// Finished games leave nothing to continue, and neither do games in
+    /// which the snake hasn't moved yet.
+    pub fn save_session(session: &mut GameSession, state: PlayState) {
+        // Replays are never saved, to keep the player's own game
+        if session.is_playback() {
+            return;
+        }
+
+        if state.is_finished() {
+            // A crash that could still have been rewound counts from now on
+            session.finish_pending();
+            SaveGame::remove();
+            return;
+        }
+
+        // Continued games wait in the ready state, but have been started
+        if session.game.ticks == 0 {
+            return;
+        }
+
+        SaveGame::new(session.clone(), state == PlayState::Paused).save();
+    }
+
+    pub fn remove() {
+        if let Err(err) = storage::remove(&storage::data_dir(), FILE_NAME) {
+            eprintln!("Unable to remove the saved game: {}", err);
+        }
+    }
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LoadError::Missing => write!(f, "no saved game"),
+            LoadError::Io(err) => write!(f, "{}", err),
+            LoadError::Invalid(err) => write!(f, "invalid save file: {}", err),
+            LoadError::UnsupportedVersion(Some(version)) => write!(
+                f,
+                "save file version {} is not supported, expected {}",
+                version, VERSION
+            ),
+            LoadError::UnsupportedVersion(None) => write!(f, "save file has no version"),
+        }
+    }
+}
+
+impl error::Error for LoadError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::Direction;
+    use crate::game_mode::GameMode;
+    use crate::settings::Settings;
+
+    #[test]
+    fn test_round_trip() {
+        let mut session =
+            GameSession::new(GameMode::TimeAttack { seconds: 60 }, Settings::default());
+        session.game.queue_direction(Direction::Left);
+        let json = serde_json::to_string(&SaveGame::new(session.clone(), true)).unwrap();
+
+        let mut restored = SaveGame::from_json(&json).unwrap();
+
+        assert!(restored.is_paused);
+        assert_eq!(restored.session.game.snake, session.game.snake);
+        assert_eq!(restored.session.game.apples, session.game.apples);
+        for _ in 0..300 {
+            assert_eq!(restored.session.game.update(), session.game.update());
+        }
+        assert_eq!(restored.session.game.apples, session.game.apples);
+    }
+
+    #[test]
+    fn test_unsupported_version() {
+        let session = GameSession::new(GameMode::Endless, Settings::default());
+        let mut save = SaveGame::new(session, false);
+        save.version = VERSION + 1;
+        let json = serde_json::to_string(&save).unwrap();
+
+        match SaveGame::from_json(&json) {
+            Err(LoadError::UnsupportedVersion(Some(version))) => {
+                assert_eq!(version, VERSION as u64 + 1)
+            }
+            other => panic!("unexpected result {:?}", other.map(|save| save.version)),
+        }
+        assert!(matches!(
+            SaveGame::from_json("{\"session\": {}}"),
+            Err(LoadError::UnsupportedVersion(None))
+        ));
+    }
+}
@@ -0,0 +1,128 @@
+use super::{Drawable, Enterable, EventHandler, Screen, Updatable};
+use crate::achievements::{self, Achievement, Achievements};
+use crate::alert::Alert;
+use crate::assets;
+use crate::color::Color;
+use crate::config;
+use crate::i18n;
+use crate::lazy_drawable::LazyDrawable;
+use crate::storage;
+use crate::{CurrentScreen, ScreenName, WINDOW_HEIGHT, WINDOW_WIDTH};
+use tetra::graphics::text::Text;
+use tetra::graphics::DrawParams;
+use tetra::input::{Key, MouseButton};
+use tetra::math::Vec2;
+use tetra::{graphics, Context, Event};
+
+pub struct AchievementList {
+    pub player: String,
+    pub achievements: Achievements,
+}
+
+impl AchievementList {
+    pub fn new() -> AchievementList {
+        AchievementList {
+            player: achievements::player_name(),
+            achievements: Achievements::default(),
+        }
+    }
+}
+
+impl Drawable for AchievementList {
+    fn draw(&mut self, ctx: &mut Context) -> tetra::Result {
+        let title_font = assets::font(ctx, 40.0)?;
+        Alert::draw_text(ctx, i18n::tr("achievements"), title_font, 30.0)?;
+
+        let entry_font = assets::font(ctx, 18.0)?;
+        for (index, achievement) in Achievement::ALL.iter().enumerate() {
+            let is_unlocked = self.achievements.is_unlocked(&self.player, *achievement);
+            let color = if is_unlocked {
+                config::ACHIEVEMENT_UNLOCKED_COLOR
+            } else {
+                config::ACHIEVEMENT_LOCKED_COLOR
+            };
+
+            let text = Text::new(
+                format!(
+                    "{}  {}  -  {}",
+                    if is_unlocked { "[x]" } else { "[ ]" },
+                    achievement.title(),
+                    achievement.description()
+                ),
+                entry_font.clone(),
+            );
+            graphics::draw(
+                ctx,
+                &text,
+                DrawParams::new()
+                    .color(color.as_tetra())
+                    .position(Vec2::new(80.0, 110.0 + index as f32 * 36.0)),
+            );
+        }
+
+        let hint_font = assets::font(ctx, 16.0)?;
+        Alert::draw_text(
+            ctx,
+            i18n::tr_with("achievements-hint", &[("player", &self.player)]),
+            hint_font,
+            (WINDOW_HEIGHT - 40) as f32,
+        )?;
+
+        Ok(())
+    }
+}
+
+pub struct AchievementsScreen {
+    pub list: LazyDrawable<AchievementList>,
+}
+
+impl AchievementsScreen {
+    pub fn try_new(ctx: &mut Context) -> tetra::Result<AchievementsScreen> {
+        Ok(AchievementsScreen {
+            list: LazyDrawable::new(
+                AchievementList::new(),
+                graphics::Canvas::new(ctx, WINDOW_WIDTH as i32, WINDOW_HEIGHT as i32)?,
+                None,
+            ),
+        })
+    }
+}
+
+impl Drawable for AchievementsScreen {
+    fn draw(&mut self, ctx: &mut Context) -> tetra::Result {
+        graphics::clear(ctx, Color::rgba(0, 0, 0, 1.0).into());
+
+        self.list.draw(ctx)?;
+
+        Ok(())
+    }
+}
+
+impl Updatable for AchievementsScreen {}
+
+impl EventHandler for AchievementsScreen {
+    fn event(&mut self, screen: &mut CurrentScreen, event: Event) {
+        match event {
+            Event::KeyPressed {
+                key: Key::Escape | Key::Backspace | Key::Space | Key::Enter | Key::NumPadEnter,
+            } => {
+                screen.use_screen(ScreenName::Start);
+            }
+            Event::MouseButtonPressed {
+                button: MouseButton::Left,
+            } => {
+                screen.use_screen(ScreenName::Start);
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Enterable for AchievementsScreen {
+    fn enter(&mut self, _screen: &mut CurrentScreen) {
+        // Achievements may have been unlocked in the meantime
+        self.list.achievements = Achievements::load(&storage::data_dir());
+    }
+}
+
+impl Screen for AchievementsScreen {}
@@ -1,32 +1,40 @@
 use crate::config;
+use crate::render::BoardLayout;
+use crate::resources;
 use crate::screen::Drawable;
 use tetra::graphics;
-use tetra::graphics::DrawParams;
 use tetra::math::Vec2;
 use tetra::Context;
 
-#[derive(Clone)]
-pub struct Background;
+/// Ground of the board, surrounded by the wall and with the walls of the level
+#[derive(Clone, PartialEq, Debug)]
+pub struct Background {
+    pub layout: BoardLayout,
+    pub walls: Vec<Vec2<i32>>,
+}
 
 impl Drawable for Background {
     fn draw(&mut self, ctx: &mut Context) -> tetra::Result {
         graphics::clear(ctx, config::PLAYGROUND_WALL_COLOR.as_tetra());
 
-        let rectangle = graphics::Texture::from_rgba(ctx, 1, 1, &[255, 255, 255, 255])?;
-        graphics::draw(
+        let playground_position = Vec2::broadcast(config::PLAYGROUND_WALL_WIDTH as f32);
+        let (position, size) = self.layout.ground_rect();
+        resources::draw_rectangle(
             ctx,
-            &rectangle,
-            DrawParams::new()
-                .scale(Vec2::new(
-                    crate::PLAYGROUND_WIDTH as f32,
-                    crate::PLAYGROUND_HEIGHT as f32,
-                ))
-                .position(Vec2::new(
-                    config::PLAYGROUND_WALL_WIDTH as f32,
-                    config::PLAYGROUND_WALL_WIDTH as f32,
-                ))
-                .color(config::PLAYGROUND_GROUND_COLOR.as_tetra()),
-        );
+            playground_position + position,
+            size,
+            &config::PLAYGROUND_GROUND_COLOR,
+        )?;
+
+        for wall in self.walls.iter() {
+            let (position, size) = self.layout.wall_rect(*wall);
+            resources::draw_rectangle(
+                ctx,
+                playground_position + position,
+                size,
+                &config::PLAYGROUND_WALL_COLOR,
+            )?;
+        }
 
         Ok(())
     }
@@ -0,0 +1,38 @@
+use crate::alert::Alert;
+use crate::assets;
+use crate::config;
+use crate::countdown::Countdown;
+use crate::resources;
+use crate::screen::Drawable;
+use crate::{PLAYGROUND_HEIGHT, WINDOW_WIDTH};
+use tetra::math::Vec2;
+use tetra::Context;
+
+const FONT_SIZE: f32 = 96.0;
+const BAND_HEIGHT: f32 = 140.0;
+
+impl Drawable for Countdown {
+    fn draw(&mut self, ctx: &mut Context) -> tetra::Result {
+        if self.is_finished() {
+            return Ok(());
+        }
+
+        let center_y = PLAYGROUND_HEIGHT as f32 / 2.0;
+        resources::draw_rectangle(
+            ctx,
+            Vec2::new(0.0, center_y - BAND_HEIGHT / 2.0),
+            Vec2::new(WINDOW_WIDTH as f32, BAND_HEIGHT),
+            &config::ALERT_BACKGROUND_COLOR,
+        )?;
+
+        let font = assets::font(ctx, FONT_SIZE)?;
+        Alert::draw_text(
+            ctx,
+            self.seconds_left().to_string(),
+            font,
+            center_y - FONT_SIZE * 0.6,
+        )?;
+
+        Ok(())
+    }
+}
@@ -1,7 +1,7 @@
 use crate::alert::Alert;
+use crate::assets;
 use crate::screen::Drawable;
-use tetra::graphics::text::Font;
-use tetra::{graphics, Context};
+use tetra::Context;
 
 #[derive(Clone)]
 pub struct GameOverAlert {
@@ -9,30 +9,18 @@ pub struct GameOverAlert {
 
     pub score: u16,
     pub score_label: String,
-
-    font_builder: graphics::text::VectorFontBuilder,
-    score_font: Option<Font>,
-    score_label_font: Option<Font>,
+    pub details: Vec<String>,
 }
 
 impl GameOverAlert {
-    pub fn try_new<T: Into<String>>(
-        base_alert: Alert,
-        score: u16,
-        score_label: T,
-    ) -> tetra::Result<GameOverAlert> {
-        Ok(GameOverAlert {
+    pub fn new<T: Into<String>>(base_alert: Alert, score: u16, score_label: T) -> GameOverAlert {
+        GameOverAlert {
             base_alert,
 
             score,
             score_label: score_label.into(),
-
-            font_builder: graphics::text::VectorFontBuilder::new(
-                "./assets/fonts/digitalt/digitalt.ttf",
-            )?,
-            score_font: None,
-            score_label_font: None,
-        })
+            details: Vec::new(),
+        }
     }
 }
 
@@ -40,18 +28,15 @@ impl Drawable for GameOverAlert {
     fn draw(&mut self, ctx: &mut Context) -> tetra::Result {
         self.base_alert.draw(ctx)?;
 
-        if self.score_font.is_none() {
-            self.score_font = Some(self.font_builder.with_size(ctx, 64.0)?);
-        }
-        if let Some(score_font) = &self.score_font {
-            Alert::draw_text(ctx, self.score.to_string(), score_font.clone(), 290.0)?;
-        }
+        let score_font = assets::font(ctx, 64.0)?;
+        Alert::draw_text(ctx, self.score.to_string(), score_font, 290.0)?;
 
-        if self.score_label_font.is_none() {
-            self.score_label_font = Some(self.font_builder.with_size(ctx, 16.0)?);
-        }
-        if let Some(score_label_font) = &self.score_label_font {
-            Alert::draw_text(ctx, &self.score_label, score_label_font.clone(), 270.0)?;
+        let small_font = assets::font(ctx, 16.0)?;
+        Alert::draw_text(ctx, &self.score_label, small_font.clone(), 270.0)?;
+
+        for (index, detail) in self.details.iter().enumerate() {
+            let y = 375.0 + index as f32 * 24.0;
+            Alert::draw_text(ctx, detail, small_font.clone(), y)?;
         }
 
         Ok(())
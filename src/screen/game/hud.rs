@@ -0,0 +1,151 @@
+use crate::assets;
+use crate::config;
+use crate::i18n;
+use crate::item::ItemKind;
+use crate::screen::Drawable;
+use tetra::graphics::text::{Font, Text};
+use tetra::graphics::DrawParams;
+use tetra::math::Vec2;
+use tetra::{graphics, Context};
+
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct HudValues {
+    pub score: u32,
+    pub length: u32,
+    pub elapsed_seconds: u32,
+    pub apples_per_minute: f32,
+    pub speed: f64,
+    pub best_score: Option<u32>,
+    /// Mode specific label and value
+    pub mode_status: Option<(String, String)>,
+    /// Active effects with their remaining ticks
+    pub effects: Vec<(ItemKind, u32)>,
+}
+
+impl HudValues {
+    fn entries(&self) -> Vec<(String, String)> {
+        let mut entries = self.mode_status.iter().cloned().collect::<Vec<_>>();
+        entries.extend(vec![
+            (i18n::tr("score"), self.score.to_string()),
+            (i18n::tr("length"), self.length.to_string()),
+            (
+                i18n::tr("time"),
+                format!(
+                    "{}:{:02}",
+                    self.elapsed_seconds / 60,
+                    self.elapsed_seconds % 60
+                ),
+            ),
+            (
+                i18n::tr("apples-per-minute"),
+                format!("{:.1}", self.apples_per_minute),
+            ),
+            (i18n::tr("speed"), format!("{:.1}", self.speed)),
+            (
+                i18n::tr("best"),
+                self.best_score
+                    .map(|score| score.to_string())
+                    .unwrap_or_else(|| "-".to_string()),
+            ),
+        ]);
+
+        entries
+    }
+
+    fn effects_description(&self) -> Option<String> {
+        if self.effects.is_empty() {
+            return None;
+        }
+
+        let effects: Vec<String> = self
+            .effects
+            .iter()
+            .map(|(kind, remaining_ticks)| format!("{} ({})", kind.label(), remaining_ticks))
+            .collect();
+
+        Some(effects.join("   "))
+    }
+}
+
+#[derive(Clone)]
+pub struct Hud {
+    pub values: HudValues,
+}
+
+impl Hud {
+    pub fn new() -> Hud {
+        Hud {
+            values: HudValues::default(),
+        }
+    }
+
+    fn draw_centered_text(
+        ctx: &mut Context,
+        content: String,
+        font: Font,
+        x: f32,
+        y: f32,
+        color: graphics::Color,
+    ) {
+        let text = Text::new(content, font);
+
+        let bounds = text
+            .get_bounds(ctx)
+            .expect("Unable to calculate bounds of text");
+
+        graphics::draw(
+            ctx,
+            &text,
+            DrawParams::new()
+                .color(color)
+                .position(Vec2::new(x - bounds.width / 2.0, y)),
+        );
+    }
+}
+
+impl Drawable for Hud {
+    fn draw(&mut self, ctx: &mut Context) -> tetra::Result {
+        graphics::clear(ctx, config::HUD_BACKGROUND_COLOR.as_tetra());
+
+        let label_font = assets::font(ctx, 12.0)?;
+        let value_font = assets::font(ctx, 22.0)?;
+
+        let entries = self.values.entries();
+        let column_width = crate::WINDOW_WIDTH as f32 / entries.len() as f32;
+
+        for (index, (label, value)) in entries.into_iter().enumerate() {
+            let x = column_width * (index as f32 + 0.5);
+
+            Hud::draw_centered_text(
+                ctx,
+                label,
+                label_font.clone(),
+                x,
+                6.0,
+                config::HUD_LABEL_COLOR.as_tetra(),
+            );
+            Hud::draw_centered_text(
+                ctx,
+                value,
+                value_font.clone(),
+                x,
+                20.0,
+                config::HUD_VALUE_COLOR.as_tetra(),
+            );
+        }
+
+        if let Some(effects) = self.values.effects_description() {
+            let effects_font = assets::font(ctx, 16.0)?;
+            Hud::draw_centered_text(
+                ctx,
+                effects,
+                effects_font,
+                crate::WINDOW_WIDTH as f32 / 2.0,
+                52.0,
+                config::HUD_EFFECT_COLOR.as_tetra(),
+            );
+        }
+
+        Ok(())
+    }
+}
@@ -1,61 +1,110 @@
-use super::{Drawable, EventHandler, Screen, Updatable};
+use super::{Drawable, Enterable, EventHandler, Screen, Updatable};
 use crate::alert::Alert;
 use crate::color::Color;
+use crate::countdown::Countdown;
+use crate::daily::DailyChallenge;
 use crate::drawable_collection::DrawableCollection;
+use crate::events::{EventStream, StreamEvent};
+use crate::game::{Direction, Game, GameEvent, GameSession, Replay};
+use crate::game_mode::{EndReason, GameMode};
+use crate::i18n;
 use crate::lazy_drawable::LazyDrawable;
+use crate::menu::{Menu, MenuChoice, MenuInput};
+use crate::play_state::{PlayState, StateMachine};
+use crate::render::BoardLayout;
+use crate::savegame::SaveGame;
+use crate::settings::Settings;
+use crate::toast::Toast;
 use crate::{
-    config, CurrentScreen, PLAYGROUND_HEIGHT, PLAYGROUND_WIDTH, WINDOW_HEIGHT, WINDOW_WIDTH,
+    config, CurrentScreen, ScreenName, PLAYGROUND_HEIGHT, PLAYGROUND_WIDTH, WINDOW_HEIGHT,
+    WINDOW_WIDTH,
 };
 use background::Background;
-use direction::Direction;
 use game_over_alert::GameOverAlert;
-use rand::Rng;
-use snake::Snake;
-use tetra::input::{Key, MouseButton};
+use hud::{Hud, HudValues};
+use pause_menu::PauseAction;
+use tetra::input::{GamepadButton, Key, MouseButton};
 use tetra::math::Vec2;
 use tetra::{graphics, Context, Event};
 use tile::Tile;
 
 mod background;
-mod direction;
+mod countdown;
 mod game_over_alert;
-mod snake;
+mod hud;
+mod pause_menu;
 mod tile;
 
 pub struct GameScreen {
-    pub is_locked: bool,
-    pub is_paused: bool,
-    pub is_game_over: bool,
+    pub state: PlayState,
+    pub is_rewinding: bool,
+    rewind_updates: u32,
+
+    pub session: GameSession,
+    pub events: EventStream,
+    /// Settings that the next game uses, which have been changed during the
+    /// current one
+    next_settings: Option<Settings>,
+    /// Settings of the player, which the daily challenge has replaced
+    player_settings: Option<Settings>,
+    /// Choice of the pause menu that waits for confirmation
+    pending_action: Option<PauseAction>,
 
     pub background: LazyDrawable<Background>,
-    pub snake: LazyDrawable<Snake>,
-    snake_direction_queue: Vec<Direction>,
+    pub snake: LazyDrawable<DrawableCollection<Tile>>,
     pub apples: LazyDrawable<DrawableCollection<Tile>>,
-    pub pause_alert: LazyDrawable<Alert>,
+    pub pause_menu: LazyDrawable<Menu<PauseAction>>,
+    pub confirm_dialog: LazyDrawable<Menu<bool>>,
     pub game_over_alert: LazyDrawable<GameOverAlert>,
+    pub victory_alert: LazyDrawable<GameOverAlert>,
+    pub hud: LazyDrawable<Hud>,
+    pub toast: LazyDrawable<Toast>,
+    pub countdown: LazyDrawable<Countdown>,
 }
 
 impl GameScreen {
-    pub fn try_new(ctx: &mut Context) -> tetra::Result<GameScreen> {
+    /// Plays games with the given settings, or the replay if there is one
+    pub fn try_new(
+        ctx: &mut Context,
+        settings: Settings,
+        replay: Option<Replay>,
+    ) -> tetra::Result<GameScreen> {
+        let session = match replay {
+            Some(replay) => GameSession::from_replay(replay),
+            None => GameSession::new(GameMode::default(), settings),
+        };
+        let layout = BoardLayout::fit(
+            session.game.settings.board_width,
+            session.game.settings.board_height,
+        );
+
         let mut state = GameScreen {
-            is_locked: false,
-            is_paused: false,
-            is_game_over: false,
+            state: PlayState::Ready,
+            is_rewinding: false,
+            rewind_updates: 0,
+
+            session,
+            events: EventStream::default(),
+            next_settings: None,
+            player_settings: None,
+            pending_action: None,
 
             background: LazyDrawable::new(
-                Background,
+                Background {
+                    layout,
+                    walls: Vec::new(),
+                },
                 graphics::Canvas::new(ctx, WINDOW_WIDTH as i32, WINDOW_HEIGHT as i32)?,
                 None,
             ),
             snake: LazyDrawable::new(
-                Snake::new(),
+                DrawableCollection::new(),
                 graphics::Canvas::new(ctx, PLAYGROUND_WIDTH as i32, PLAYGROUND_HEIGHT as i32)?,
                 Vec2::new(
                     config::PLAYGROUND_WALL_WIDTH as f32,
                     config::PLAYGROUND_WALL_WIDTH as f32,
                 ),
             ),
-            snake_direction_queue: Vec::new(),
             apples: LazyDrawable::new(
                 DrawableCollection::new(),
                 graphics::Canvas::new(ctx, PLAYGROUND_WIDTH as i32, PLAYGROUND_HEIGHT as i32)?,
@@ -64,192 +113,477 @@ impl GameScreen {
                     config::PLAYGROUND_WALL_WIDTH as f32,
                 ),
             ),
-            pause_alert: LazyDrawable::new(
-                Alert::try_new("Paused", "Press 'ESC' to resume")?,
+            pause_menu: LazyDrawable::new(
+                PauseAction::menu(),
+                graphics::Canvas::new(ctx, WINDOW_WIDTH as i32, WINDOW_HEIGHT as i32)?,
+                None,
+            ),
+            confirm_dialog: LazyDrawable::new(
+                Menu::confirm("", ""),
                 graphics::Canvas::new(ctx, WINDOW_WIDTH as i32, WINDOW_HEIGHT as i32)?,
                 None,
             ),
             game_over_alert: LazyDrawable::new(
-                GameOverAlert::try_new(
-                    Alert::try_new("Game over", "Press 'R' to restart")?,
+                GameOverAlert::new(
+                    Alert::new(i18n::tr("game-over"), i18n::tr("game-over-hint")),
                     0,
-                    "Score",
-                )?,
+                    i18n::tr("score"),
+                ),
                 graphics::Canvas::new(ctx, WINDOW_WIDTH as i32, WINDOW_HEIGHT as i32)?,
                 None,
             ),
+            victory_alert: LazyDrawable::new(
+                GameOverAlert::new(
+                    Alert::new(i18n::tr("victory"), i18n::tr("victory-hint")),
+                    0,
+                    i18n::tr("score"),
+                ),
+                graphics::Canvas::new(ctx, WINDOW_WIDTH as i32, WINDOW_HEIGHT as i32)?,
+                None,
+            ),
+            hud: LazyDrawable::new(
+                Hud::new(),
+                graphics::Canvas::new(ctx, WINDOW_WIDTH as i32, config::HUD_HEIGHT as i32)?,
+                Vec2::new(0.0, (WINDOW_HEIGHT - config::HUD_HEIGHT) as f32),
+            ),
+            toast: LazyDrawable::new(
+                Toast::new(i18n::tr("achievement-unlocked")),
+                graphics::Canvas::new(ctx, WINDOW_WIDTH as i32, config::TOAST_HEIGHT as i32)?,
+                None,
+            ),
+            countdown: LazyDrawable::new(
+                Countdown::new(),
+                graphics::Canvas::new(ctx, WINDOW_WIDTH as i32, PLAYGROUND_HEIGHT as i32)?,
+                None,
+            ),
         };
 
-        state.reset();
+        state.update_background();
+        state.update_board();
+        state.update_hud();
 
         Ok(state)
     }
 
-    pub fn spawn_apple(&mut self) -> Option<&Tile> {
-        let apple = Tile {
-            position: self.choose_apple_position()?,
-            color: config::APPLE_COLOR,
-        };
-
-        self.apples.push(apple);
-
-        self.apples.last()
+    fn game(&self) -> &Game {
+        &self.session.game
     }
 
-    pub fn choose_apple_position(&self) -> Option<Vec2<i32>> {
-        let possible_positions = self.all_possible_apple_positions();
-        if possible_positions.is_empty() {
-            return None;
+    /// Changes the state if the transition is allowed, running the hooks of
+    /// both states
+    fn change_state(&mut self, next: PlayState) {
+        if let Err(err) = self.transition(next) {
+            eprintln!("Unable to change the state of the game: {}", err);
         }
+    }
 
-        let index = rand::thread_rng().gen_range(0, possible_positions.len());
+    pub fn pause(&mut self) {
+        self.change_state(PlayState::Paused);
+    }
 
-        possible_positions.get(index).cloned()
+    pub fn resume(&mut self) {
+        self.change_state(self.moving_state());
     }
 
-    pub fn all_possible_apple_positions(&self) -> Vec<Vec2<i32>> {
-        let tail_positions = self.snake.tail.iter().map(|tile| tile.position);
+    /// Counts down before the snake moves, unless the countdown is disabled
+    fn moving_state(&self) -> PlayState {
+        if self.game().settings.countdown > 0 {
+            PlayState::Countdown
+        } else {
+            PlayState::Playing
+        }
+    }
 
-        let apple_positions = self.apples.iter().map(|tile| tile.position);
+    /// Settings of the next game, which may differ from the current one
+    pub fn next_settings(&self) -> &Settings {
+        self.next_settings
+            .as_ref()
+            .unwrap_or(&self.session.game.settings)
+    }
 
-        let mut blacklist = vec![
-            self.snake.head.position,
-            self.snake.get_next_head_position(),
-        ];
-        blacklist.extend(tail_positions);
-        blacklist.extend(apple_positions);
+    /// Uses the settings from the next game on, or right away if the current
+    /// game hasn't started yet
+    pub fn use_settings(&mut self, settings: Settings) {
+        // Replays keep the settings they were recorded with
+        if self.session.is_playback() || settings == self.session.game.settings {
+            self.next_settings = None;
+            return;
+        }
 
-        let mut possible_positions: Vec<Vec2<i32>> = Vec::new();
-        for x in 0..config::TILE_COUNT_X {
-            for y in 0..config::TILE_COUNT_Y {
-                let pos = Vec2::new(x as i32, y as i32);
+        self.next_settings = Some(settings);
+        if self.state == PlayState::Ready {
+            self.restart();
+        }
+    }
 
-                if blacklist.contains(&pos) {
-                    continue;
-                }
+    /// Settings that the player has chosen, which the daily challenge
+    /// doesn't change
+    pub fn player_settings(&self) -> &Settings {
+        self.player_settings
+            .as_ref()
+            .unwrap_or_else(|| self.next_settings())
+    }
 
-                possible_positions.push(pos);
-            }
+    /// Changes the player's settings, which are put aside while the daily
+    /// challenge is played
+    pub fn use_player_settings(&mut self, settings: Settings) {
+        match &mut self.player_settings {
+            Some(player_settings) => *player_settings = settings,
+            None => self.use_settings(settings),
+        }
+    }
+
+    /// Plays the challenge with its settings until another game is chosen
+    pub fn play_daily(&mut self, challenge: DailyChallenge) {
+        if self.player_settings.is_none() {
+            self.player_settings = Some(self.next_settings().clone());
         }
 
-        possible_positions
+        let settings = challenge.settings.clone();
+        self.session.use_daily(Some(challenge));
+        self.use_settings(settings);
     }
 
-    fn position_is_out_of_bounds(&self, position: &Vec2<i32>) -> bool {
-        position.x < 0
-            || position.x > (config::TILE_COUNT_X - 1) as i32
-            || position.y < 0
-            || position.y > (config::TILE_COUNT_Y - 1) as i32
+    /// Goes back to the player's settings after the daily challenge
+    pub fn leave_daily(&mut self) {
+        self.session.use_daily(None);
+        if let Some(settings) = self.player_settings.take() {
+            self.use_settings(settings);
+        }
     }
 
-    pub fn pause(&mut self) {
-        self.is_paused = true;
-        self.is_locked = true;
+    /// Saves the game in progress, so that it can be continued from the
+    /// start screen
+    pub fn save(&mut self) {
+        SaveGame::save_session(&mut self.session, self.state);
     }
 
-    pub fn resume(&mut self) {
-        self.is_paused = false;
-        self.is_locked = false;
+    fn continue_saved_game(&mut self, screen: &mut CurrentScreen) {
+        let saved_game = match SaveGame::load() {
+            Ok(saved_game) => saved_game,
+            Err(err) => {
+                eprintln!("Unable to continue the saved game: {}", err);
+                return;
+            }
+        };
+        // A save can only be continued once
+        SaveGame::remove();
+
+        screen.use_game_mode(saved_game.session.game.mode);
+        self.session = saved_game.session;
+        if self.state != PlayState::Ready {
+            self.change_state(PlayState::Ready);
+        }
+        if saved_game.is_paused {
+            self.pause();
+        }
+
+        self.update_background();
+        self.update_board();
+        self.update_hud();
     }
 
-    pub fn game_over(&mut self) {
-        self.is_game_over = true;
-        self.is_locked = true;
+    /// Shows the summary of the finished game on the alert of its outcome
+    fn show_summary(&mut self) {
+        let summary = match &self.session.summary {
+            Some(summary) => summary.clone(),
+            None => return,
+        };
 
-        self.game_over_alert.score = self.snake.tail.len() as u16;
-        self.game_over_alert.updated = true;
+        let alert = if self.state == PlayState::Victory {
+            &mut self.victory_alert
+        } else {
+            &mut self.game_over_alert
+        };
+        alert.base_alert.title = summary.title;
+        alert.score = summary.score as u16;
+        alert.score_label = summary.score_label;
+        alert.details = summary.details;
+    }
+
+    /// Restarts the game if another mode has been selected in the meantime
+    fn use_mode(&mut self, mode: &GameMode) {
+        if &self.game().mode == mode {
+            return;
+        }
+
+        self.session.game.mode = *mode;
+        self.restart();
     }
 
     pub fn restart(&mut self) {
-        self.is_game_over = false;
-        self.is_paused = false;
-        self.is_locked = false;
+        if self.state != PlayState::Ready {
+            self.change_state(PlayState::Ready);
+        }
 
-        self.reset();
+        if let Some(settings) = self.next_settings.take() {
+            self.session.game.settings = settings;
+        }
+        self.session.restart();
+        self.update_background();
+        self.update_board();
+        self.update_hud();
 
         self.game_over_alert.score = 0;
-        self.game_over_alert.updated = true;
     }
 
-    fn reset(&mut self) {
-        self.snake.inner = Snake::new();
-        let snake_start_size = std::env::var("SNAKE_START_SIZE")
-            .unwrap_or("0".to_string())
-            .parse::<u32>()
-            .expect("Invalid SNAKE_START_SIZE");
-        for _ in 0..snake_start_size {
-            self.snake.grow_tail();
-        }
-        self.snake.updated = true;
-
-        self.apples.clear();
-        let apple_count = std::env::var("APPLE_COUNT")
-            .unwrap_or("1".to_string())
-            .parse::<u32>()
-            .expect("Invalid APPLE_COUNT");
-        for _ in 0..apple_count {
-            if self.spawn_apple().is_none() {
-                break;
+    fn handle_pause_event(&mut self, screen: &mut CurrentScreen, event: Event) {
+        if self.pending_action.is_none() {
+            match event {
+                Event::KeyPressed { key: Key::F5 } => {
+                    self.save();
+                    self.pause_menu.hint = Some(i18n::tr("game-saved"));
+                    return;
+                }
+                Event::KeyPressed { key: Key::P }
+                | Event::GamepadButtonPressed {
+                    button: GamepadButton::Start,
+                    ..
+                } => {
+                    self.resume();
+                    return;
+                }
+                _ => {}
             }
         }
-        self.apples.updated = true;
-    }
-}
 
-impl Updatable for GameScreen {
-    fn update(&mut self, _screen: &mut CurrentScreen) {
-        if self.is_locked {
+        let input = match MenuInput::from_event(&event) {
+            Some(input) => input,
+            None => return,
+        };
+
+        if let Some(action) = self.pending_action {
+            match self.confirm_dialog.handle_input(input) {
+                Some(MenuChoice::Select(true)) => {
+                    self.pending_action = None;
+                    self.run_pause_action(screen, action);
+                }
+                Some(MenuChoice::Select(false)) | Some(MenuChoice::Back) => {
+                    self.pending_action = None;
+                }
+                _ => {}
+            }
+
             return;
         }
 
-        if !self.snake_direction_queue.is_empty() {
-            let dir_match =
-                self.snake_direction_queue
-                    .iter()
-                    .enumerate()
-                    .rfind(|(_index, &dir)| {
-                        dir != self.snake.direction && dir != self.snake.direction.opposite()
-                    });
-
-            if let Some((index, &dir)) = dir_match {
-                self.snake.direction = dir;
-                self.snake_direction_queue = Vec::from(&self.snake_direction_queue[(index + 1)..]);
-            } else {
-                self.snake_direction_queue.clear();
+        match self.pause_menu.handle_input(input) {
+            Some(MenuChoice::Select(action)) => match action.confirmation() {
+                Some((question, consequence)) => {
+                    self.confirm_dialog
+                        .set(Menu::confirm(i18n::tr(question), i18n::tr(consequence)));
+                    self.pending_action = Some(action);
+                }
+                None => self.run_pause_action(screen, action),
+            },
+            Some(MenuChoice::Back) => self.resume(),
+            Some(MenuChoice::Adjust(..)) | None => {}
+        }
+    }
+
+    fn run_pause_action(&mut self, screen: &mut CurrentScreen, action: PauseAction) {
+        match action {
+            PauseAction::Resume => self.resume(),
+            PauseAction::Restart => self.restart(),
+            PauseAction::Settings => screen.use_screen(ScreenName::Settings),
+            PauseAction::QuitToTitle => {
+                self.restart();
+                screen.use_screen(ScreenName::Start);
             }
+            // The paused game is saved when the window closes
+            PauseAction::QuitGame => screen.request_quit(),
         }
+    }
+
+    fn layout(&self) -> BoardLayout {
+        let settings = &self.session.game.settings;
+
+        BoardLayout::fit(settings.board_width, settings.board_height)
+    }
+
+    /// Adapts the board to the settings of the game
+    fn update_background(&mut self) {
+        self.background.set(Background {
+            layout: self.layout(),
+            walls: self.session.game.settings.walls().to_vec(),
+        });
+    }
 
-        let next_head_pos = self.snake.get_next_head_position();
+    /// Rebuilds the tiles of the snake and the items from the game
+    fn update_board(&mut self) {
+        let layout = self.layout();
+        let game = &self.session.game;
 
-        let collided_apple_index = self
+        let mut snake_tiles: Vec<Tile> = game
+            .snake
+            .tail
+            .iter()
+            .map(|position| Tile::new(position.x, position.y, config::SNAKE_TAIL_COLOR, layout))
+            .collect();
+        snake_tiles.push(Tile::new(
+            game.snake.head.x,
+            game.snake.head.y,
+            config::SNAKE_HEAD_COLOR,
+            layout,
+        ));
+        self.snake.set(snake_tiles.into());
+
+        let apple_tiles: Vec<Tile> = game
             .apples
             .iter()
-            .position(|apple| apple.position == next_head_pos);
+            .map(|apple| {
+                Tile::new(
+                    apple.position.x,
+                    apple.position.y,
+                    apple.kind.color(),
+                    layout,
+                )
+            })
+            .collect();
+        self.apples.set(apple_tiles.into());
+    }
+
+    fn update_hud(&mut self) {
+        let game = &self.session.game;
+        let progress = game.progress();
+        let elapsed_seconds = progress.elapsed_seconds;
+        let apples_per_minute = if elapsed_seconds > 0.0 {
+            // Rounded to the displayed precision to avoid redrawing every frame
+            ((game.apples_eaten as f64 / elapsed_seconds * 600.0).round() / 10.0) as f32
+        } else {
+            0.0
+        };
 
-        if let Some(index) = collided_apple_index {
-            let new_position = self.choose_apple_position();
+        let values = HudValues {
+            score: progress.score,
+            length: progress.length,
+            elapsed_seconds: elapsed_seconds as u32,
+            apples_per_minute,
+            speed: game.speed,
+            best_score: self.session.best_score(),
+            mode_status: game.mode.status(&progress),
+            effects: game
+                .effects
+                .iter()
+                .map(|effect| (effect.kind, effect.remaining_ticks))
+                .collect(),
+        };
 
-            if let Some(new_position) = new_position {
-                let apple = self.apples.get_mut(index).expect("Could not find apple");
-                apple.position = new_position;
-            } else {
-                self.apples.remove(index);
+        if self.hud.values != values {
+            self.hud.values = values;
+        }
+    }
+}
+
+impl StateMachine for GameScreen {
+    fn state(&self) -> PlayState {
+        self.state
+    }
+
+    fn set_state(&mut self, state: PlayState) {
+        self.state = state;
+    }
+
+    fn exit_state(&mut self, state: PlayState, next: PlayState) {
+        match state {
+            PlayState::Ready if next != PlayState::Ready => {
+                self.events.publish(StreamEvent::started(self.game()));
+            }
+            PlayState::Playing => {
+                self.is_rewinding = false;
+            }
+            PlayState::Paused if next != PlayState::Ready => {
+                self.events.publish(StreamEvent::Resumed {
+                    tick: self.game().ticks,
+                });
+            }
+            _ => {}
+        }
+    }
+
+    fn enter_state(&mut self, state: PlayState, _previous: PlayState) {
+        match state {
+            PlayState::Countdown => {
+                let seconds = self.game().settings.countdown;
+                self.countdown.start(seconds);
+            }
+            PlayState::Paused => {
+                self.events.publish(StreamEvent::Paused {
+                    tick: self.game().ticks,
+                });
+
+                self.pending_action = None;
+                self.pause_menu.select(PauseAction::Resume);
+                self.pause_menu.hint = Some(i18n::tr("paused-hint"));
             }
-            self.apples.updated = true;
+            PlayState::GameOver | PlayState::Victory => {
+                self.show_summary();
+                // The saved game has been played to its end
+                SaveGame::remove();
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Updatable for GameScreen {
+    fn update(&mut self, screen: &mut CurrentScreen) {
+        self.use_mode(screen.game_mode());
 
-            self.snake.grow_tail();
-            self.snake.updated = true;
+        self.toast.modify(|toast| toast.advance());
+
+        if self.state == PlayState::Ready {
+            self.change_state(self.moving_state());
+        }
+        if self.state == PlayState::Countdown {
+            // Turns are buffered until the snake moves
+            self.countdown.modify(|countdown| countdown.advance());
+            if self.countdown.is_finished() {
+                self.change_state(PlayState::Playing);
+            }
+            return;
+        }
+        if self.state != PlayState::Playing {
+            return;
         }
 
-        if self.position_is_out_of_bounds(&next_head_pos)
-            || self.snake.position_collides(&next_head_pos)
-        {
-            self.game_over();
+        if self.is_rewinding && self.game().mode.allows_rewind() {
+            self.rewind_updates += 1;
+            if self.rewind_updates >= config::REWIND_UPDATES_PER_TICK {
+                self.rewind_updates = 0;
+                if self.session.rewind() {
+                    self.update_board();
+                }
+            }
+
+            self.update_hud();
             return;
         }
 
-        self.snake.move_forward();
-        self.snake.updated = true;
+        let update = self.session.update();
+        self.events
+            .publish_game_events(&update.events, &self.session.game);
+
+        for achievement in update.unlocked {
+            self.toast.push(achievement.title());
+        }
+
+        if !update.events.is_empty() {
+            self.update_board();
+        }
+        if update
+            .events
+            .iter()
+            .any(|event| matches!(event, GameEvent::Ended { .. }))
+        {
+            if self.game().end == Some(EndReason::Victory) {
+                self.change_state(PlayState::Victory);
+            } else {
+                self.change_state(PlayState::GameOver);
+            }
+        }
+
+        self.update_hud();
     }
 }
 
@@ -260,11 +594,21 @@ impl Drawable for GameScreen {
         self.background.draw(ctx)?;
         self.apples.draw(ctx)?;
         self.snake.draw(ctx)?;
-        if self.is_game_over {
-            self.game_over_alert.draw(ctx)?;
+        self.hud.draw(ctx)?;
+        match self.state {
+            PlayState::Victory => self.victory_alert.draw(ctx)?,
+            PlayState::GameOver => self.game_over_alert.draw(ctx)?,
+            PlayState::Paused => {
+                self.pause_menu.draw(ctx)?;
+                if self.pending_action.is_some() {
+                    self.confirm_dialog.draw(ctx)?;
+                }
+            }
+            PlayState::Countdown => self.countdown.draw(ctx)?,
+            PlayState::Ready | PlayState::Playing => {}
         }
-        if self.is_paused {
-            self.pause_alert.draw(ctx)?;
+        if self.toast.is_visible() {
+            self.toast.draw(ctx)?;
         }
 
         Ok(())
@@ -272,63 +616,71 @@ impl Drawable for GameScreen {
 }
 
 impl EventHandler for GameScreen {
-    fn event(&mut self, _screen: &mut CurrentScreen, event: Event) {
-        if !self.is_locked {
-            match event {
+    fn event(&mut self, screen: &mut CurrentScreen, event: Event) {
+        self.use_mode(screen.game_mode());
+
+        if let Event::KeyReleased {
+            key: Key::Backspace,
+        } = event
+        {
+            self.is_rewinding = false;
+        }
+
+        match self.state {
+            // Turns during the countdown are queued for the first tick
+            PlayState::Playing | PlayState::Countdown => match event {
                 Event::KeyPressed {
                     key: Key::W | Key::Up,
                 } => {
-                    self.snake_direction_queue.push(Direction::Up);
+                    self.session.queue_direction(Direction::Up);
                 }
                 Event::KeyPressed {
                     key: Key::S | Key::Down,
                 } => {
-                    self.snake_direction_queue.push(Direction::Down);
+                    self.session.queue_direction(Direction::Down);
                 }
                 Event::KeyPressed {
                     key: Key::A | Key::Left,
                 } => {
-                    self.snake_direction_queue.push(Direction::Left);
+                    self.session.queue_direction(Direction::Left);
                 }
                 Event::KeyPressed {
                     key: Key::D | Key::Right,
                 } => {
-                    self.snake_direction_queue.push(Direction::Right);
+                    self.session.queue_direction(Direction::Right);
                 }
                 Event::KeyPressed {
                     key: Key::Escape | Key::P,
                 } => {
                     self.pause();
                 }
+                Event::KeyPressed {
+                    key: Key::Backspace,
+                } if self.state == PlayState::Playing => {
+                    self.is_rewinding = true;
+                    self.rewind_updates = config::REWIND_UPDATES_PER_TICK;
+                }
+                Event::GamepadButtonPressed {
+                    button: GamepadButton::Start,
+                    ..
+                } => {
+                    self.pause();
+                }
                 Event::FocusLost => {
                     self.pause();
                 }
                 _ => {}
-            }
-
-            return;
-        }
-
-        if self.is_paused {
-            match event {
+            },
+            PlayState::Paused => self.handle_pause_event(screen, event),
+            PlayState::GameOver | PlayState::Victory => match event {
+                // The crash of a practice game can be undone
                 Event::KeyPressed {
-                    key: Key::Escape | Key::P | Key::Space | Key::Enter | Key::NumPadEnter,
-                } => {
-                    self.resume();
+                    key: Key::Backspace,
+                } if self.session.can_rewind() => {
+                    self.change_state(PlayState::Playing);
+                    self.is_rewinding = true;
+                    self.rewind_updates = config::REWIND_UPDATES_PER_TICK;
                 }
-                Event::MouseButtonPressed {
-                    button: MouseButton::Left,
-                } => {
-                    self.resume();
-                }
-                _ => {}
-            }
-
-            return;
-        }
-
-        if self.is_game_over {
-            match event {
                 Event::KeyPressed {
                     key: Key::R | Key::Space | Key::Enter | Key::NumPadEnter,
                 } => {
@@ -340,10 +692,16 @@ impl EventHandler for GameScreen {
                     self.restart();
                 }
                 _ => {}
-            }
+            },
+            PlayState::Ready => {}
+        }
+    }
+}
 
-            #[allow(clippy::needless_return)]
-            return;
+impl Enterable for GameScreen {
+    fn enter(&mut self, screen: &mut CurrentScreen) {
+        if screen.take_saved_game_request() {
+            self.continue_saved_game(screen);
         }
     }
 }
@@ -0,0 +1,52 @@
+use crate::i18n;
+use crate::menu::Menu;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum PauseAction {
+    Resume,
+    Restart,
+    Settings,
+    QuitToTitle,
+    QuitGame,
+}
+
+impl PauseAction {
+    const ALL: [PauseAction; 5] = [
+        PauseAction::Resume,
+        PauseAction::Restart,
+        PauseAction::Settings,
+        PauseAction::QuitToTitle,
+        PauseAction::QuitGame,
+    ];
+
+    pub fn menu() -> Menu<PauseAction> {
+        let items = PauseAction::ALL
+            .iter()
+            .map(|action| (*action, i18n::tr(action.label())))
+            .collect();
+        let mut menu = Menu::new(i18n::tr("paused"), items);
+        menu.hint = Some(i18n::tr("paused-hint"));
+
+        menu
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            PauseAction::Resume => "menu-resume",
+            PauseAction::Restart => "menu-restart",
+            PauseAction::Settings => "menu-settings",
+            PauseAction::QuitToTitle => "menu-quit-to-title",
+            PauseAction::QuitGame => "menu-quit-game",
+        }
+    }
+
+    /// Question and consequence to confirm before the current game is left
+    pub fn confirmation(&self) -> Option<(&'static str, &'static str)> {
+        match self {
+            PauseAction::Resume | PauseAction::Settings => None,
+            PauseAction::Restart => Some(("confirm-restart", "confirm-game-lost")),
+            PauseAction::QuitToTitle => Some(("confirm-quit-to-title", "confirm-game-lost")),
+            PauseAction::QuitGame => Some(("confirm-quit-game", "confirm-game-saved")),
+        }
+    }
+}
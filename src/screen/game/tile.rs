@@ -1,43 +1,31 @@
 use crate::color::Color;
-use crate::config;
+use crate::render::BoardLayout;
+use crate::resources;
 use crate::screen::Drawable;
 use tetra::math::Vec2;
-use tetra::{graphics, Context};
+use tetra::Context;
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq, Debug)]
 pub struct Tile {
     pub position: Vec2<i32>,
     pub color: Color,
+    pub layout: BoardLayout,
 }
 
 impl Tile {
-    pub fn new(x: i32, y: i32, color: Color) -> Tile {
+    pub fn new(x: i32, y: i32, color: Color, layout: BoardLayout) -> Tile {
         Tile {
             position: Vec2::new(x, y),
             color,
+            layout,
         }
     }
 }
 
-const TILE_SIZE: f32 = config::TILE_SIZE as f32 * 0.9;
-const MARGIN_SIZE: f32 = (config::TILE_SIZE as f32 - TILE_SIZE) / 2.0;
-
 impl Drawable for Tile {
     fn draw(&mut self, ctx: &mut Context) -> tetra::Result {
-        let rectangle = graphics::Texture::from_rgba(ctx, 1, 1, &[255, 255, 255, 255])?;
-
-        graphics::draw(
-            ctx,
-            &rectangle,
-            graphics::DrawParams::new()
-                .scale(Vec2::new(TILE_SIZE, TILE_SIZE))
-                .position(Vec2::new(
-                    (self.position.x * config::TILE_SIZE as i32) as f32 + MARGIN_SIZE,
-                    (self.position.y * config::TILE_SIZE as i32) as f32 + MARGIN_SIZE,
-                ))
-                .color(self.color.as_tetra()),
-        );
+        let (position, size) = self.layout.tile_rect(self.position);
 
-        Ok(())
+        resources::draw_rectangle(ctx, position, size, &self.color)
     }
 }
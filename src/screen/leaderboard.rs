@@ -0,0 +1,143 @@
+use super::{Drawable, Enterable, EventHandler, Screen, Updatable};
+use crate::alert::Alert;
+use crate::assets;
+use crate::color::Color;
+use crate::config;
+use crate::game_mode::GameMode;
+use crate::high_scores::HighScores;
+use crate::i18n;
+use crate::lazy_drawable::LazyDrawable;
+use crate::menu::MenuInput;
+use crate::settings::Settings;
+use crate::storage;
+use crate::{CurrentScreen, WINDOW_HEIGHT, WINDOW_WIDTH};
+use tetra::graphics::text::Text;
+use tetra::graphics::DrawParams;
+use tetra::math::Vec2;
+use tetra::{graphics, Context, Event};
+
+/// Best scores of the preset modes under the settings of the next game
+pub struct LeaderboardList {
+    pub high_scores: HighScores,
+    pub settings: Settings,
+}
+
+impl LeaderboardList {
+    pub fn new() -> LeaderboardList {
+        LeaderboardList {
+            high_scores: HighScores::default(),
+            settings: Settings::default(),
+        }
+    }
+
+    fn entries(&self) -> Vec<(String, String, String)> {
+        GameMode::PRESETS
+            .iter()
+            .map(|mode| {
+                let best = self
+                    .high_scores
+                    .best(mode, &self.settings)
+                    .map(|best| best.to_string())
+                    .unwrap_or_else(|| "-".to_string());
+                let victories = self
+                    .high_scores
+                    .victories(mode, &self.settings)
+                    .map(|victories| victories.count.to_string())
+                    .unwrap_or_else(|| "-".to_string());
+
+                (mode.to_string(), best, victories)
+            })
+            .collect()
+    }
+}
+
+impl Drawable for LeaderboardList {
+    fn draw(&mut self, ctx: &mut Context) -> tetra::Result {
+        let title_font = assets::font(ctx, 40.0)?;
+        Alert::draw_text(ctx, i18n::tr("leaderboard"), title_font, 30.0)?;
+
+        let entry_font = assets::font(ctx, 18.0)?;
+        let header = (String::new(), i18n::tr("best-score"), i18n::tr("victories"));
+        let rows = std::iter::once(header).chain(self.entries());
+        for (index, (mode, best, victories)) in rows.enumerate() {
+            let y = 100.0 + index as f32 * 36.0;
+            let color = if index == 0 {
+                config::HUD_LABEL_COLOR
+            } else {
+                config::HUD_VALUE_COLOR
+            };
+
+            for (x, content) in [(80.0, mode), (460.0, best), (600.0, victories)].iter() {
+                let text = Text::new(content.as_str(), entry_font.clone());
+                graphics::draw(
+                    ctx,
+                    &text,
+                    DrawParams::new()
+                        .color(color.as_tetra())
+                        .position(Vec2::new(*x, y)),
+                );
+            }
+        }
+
+        let hint_font = assets::font(ctx, 16.0)?;
+        Alert::draw_text(
+            ctx,
+            i18n::tr("leaderboard-hint"),
+            hint_font,
+            (WINDOW_HEIGHT - 40) as f32,
+        )?;
+
+        Ok(())
+    }
+}
+
+pub struct LeaderboardScreen {
+    pub list: LazyDrawable<LeaderboardList>,
+}
+
+impl LeaderboardScreen {
+    pub fn try_new(ctx: &mut Context) -> tetra::Result<LeaderboardScreen> {
+        Ok(LeaderboardScreen {
+            list: LazyDrawable::new(
+                LeaderboardList::new(),
+                graphics::Canvas::new(ctx, WINDOW_WIDTH as i32, WINDOW_HEIGHT as i32)?,
+                None,
+            ),
+        })
+    }
+
+    /// Shows the scores that have been achieved with the settings
+    pub fn use_settings(&mut self, settings: Settings) {
+        self.list.settings = settings;
+    }
+}
+
+impl Drawable for LeaderboardScreen {
+    fn draw(&mut self, ctx: &mut Context) -> tetra::Result {
+        graphics::clear(ctx, Color::rgba(0, 0, 0, 1.0).into());
+
+        self.list.draw(ctx)?;
+
+        Ok(())
+    }
+}
+
+impl Updatable for LeaderboardScreen {}
+
+impl EventHandler for LeaderboardScreen {
+    fn event(&mut self, screen: &mut CurrentScreen, event: Event) {
+        if let Some(MenuInput::Select | MenuInput::Back | MenuInput::Click) =
+            MenuInput::from_event(&event)
+        {
+            screen.go_back();
+        }
+    }
+}
+
+impl Enterable for LeaderboardScreen {
+    fn enter(&mut self, _screen: &mut CurrentScreen) {
+        self.list.high_scores = HighScores::load(&storage::data_dir());
+    }
+}
+
+impl Screen for LeaderboardScreen {}
@@ -1,11 +1,20 @@
 use tetra::{Context, Event};
 
+mod achievements;
 mod game;
+mod leaderboard;
+mod mode_selector;
+mod settings;
 mod start;
+mod statistics;
 
 use crate::CurrentScreen;
+pub use achievements::AchievementsScreen;
 pub use game::GameScreen;
+pub use leaderboard::LeaderboardScreen;
+pub use settings::SettingsScreen;
 pub use start::StartScreen;
+pub use statistics::StatisticsScreen;
 
 pub trait Drawable {
     fn draw(&mut self, _ctx: &mut Context) -> tetra::Result {
@@ -21,4 +30,8 @@ pub trait EventHandler {
     fn event(&mut self, _screen: &mut CurrentScreen, _event: Event) {}
 }
 
-pub trait Screen: Drawable + Updatable + EventHandler {}
+pub trait Enterable {
+    fn enter(&mut self, _screen: &mut CurrentScreen) {}
+}
+
+pub trait Screen: Drawable + Updatable + EventHandler + Enterable {}
@@ -0,0 +1,38 @@
+use crate::game_mode::GameMode;
+
+#[derive(Clone)]
+pub struct ModeSelector {
+    modes: Vec<GameMode>,
+    selected_index: usize,
+}
+
+impl ModeSelector {
+    /// Offers the presets, along with the selected mode if it is none of them
+    pub fn new(selected: GameMode) -> ModeSelector {
+        let mut modes = GameMode::PRESETS.to_vec();
+        let selected_index = match modes.iter().position(|mode| *mode == selected) {
+            Some(index) => index,
+            None => {
+                modes.push(selected);
+                modes.len() - 1
+            }
+        };
+
+        ModeSelector {
+            modes,
+            selected_index,
+        }
+    }
+
+    pub fn selected(&self) -> GameMode {
+        self.modes[self.selected_index]
+    }
+
+    pub fn select_next(&mut self) {
+        self.selected_index = (self.selected_index + 1) % self.modes.len();
+    }
+
+    pub fn select_previous(&mut self) {
+        self.selected_index = (self.selected_index + self.modes.len() - 1) % self.modes.len();
+    }
+}
@@ -0,0 +1,238 @@
+use super::{Drawable, Enterable, EventHandler, Screen, Updatable};
+use crate::color::Color;
+use crate::i18n;
+use crate::lazy_drawable::LazyDrawable;
+use crate::menu::{Menu, MenuChoice, MenuInput};
+use crate::settings::{Settings, MAX_BOARD_SIZE, MAX_COUNTDOWN, MIN_BOARD_SIZE};
+use crate::{CurrentScreen, WINDOW_HEIGHT, WINDOW_WIDTH};
+use tetra::{graphics, Context, Event};
+
+/// Fastest speed that can be chosen in the menu, in tiles per second
+const MAX_MENU_SPEED: f64 = 15.0;
+const SPEED_STEP: f64 = 0.5;
+const MAX_MENU_APPLES: u32 = 20;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum SettingsEntry {
+    Speed,
+    Apples,
+    BoardWidth,
+    BoardHeight,
+    StartSize,
+    Countdown,
+    Back,
+}
+
+impl SettingsEntry {
+    const ALL: [SettingsEntry; 7] = [
+        SettingsEntry::Speed,
+        SettingsEntry::Apples,
+        SettingsEntry::BoardWidth,
+        SettingsEntry::BoardHeight,
+        SettingsEntry::StartSize,
+        SettingsEntry::Countdown,
+        SettingsEntry::Back,
+    ];
+
+    fn label(&self, settings: &Settings) -> String {
+        let (key, value) = match self {
+            SettingsEntry::Speed => ("speed", settings.speed.to_string()),
+            SettingsEntry::Apples => ("setting-apples", settings.apple_count.to_string()),
+            SettingsEntry::BoardWidth => ("setting-board-width", settings.board_width.to_string()),
+            SettingsEntry::BoardHeight => {
+                ("setting-board-height", settings.board_height.to_string())
+            }
+            SettingsEntry::StartSize => {
+                ("setting-start-size", settings.snake_start_size.to_string())
+            }
+            SettingsEntry::Countdown => ("setting-countdown", settings.countdown.to_string()),
+            SettingsEntry::Back => return i18n::tr("menu-back"),
+        };
+
+        format!("{}:  < {} >", i18n::tr(key), value)
+    }
+
+    /// Changes the setting by the step, unless the result would be out of
+    /// range or unplayable. Returns whether the setting has changed.
+    fn adjust(&self, settings: &mut Settings, step: i32) -> bool {
+        let mut changed = settings.clone();
+        let step_by = |value: u32, min: u32, max: u32| {
+            (value as i64 + step as i64).clamp(min as i64, max as i64) as u32
+        };
+
+        match self {
+            SettingsEntry::Speed => {
+                changed.speed =
+                    (changed.speed + step as f64 * SPEED_STEP).clamp(SPEED_STEP, MAX_MENU_SPEED);
+            }
+            SettingsEntry::Apples => {
+                changed.apple_count = step_by(changed.apple_count, 1, MAX_MENU_APPLES);
+            }
+            // Levels decide the size of their board
+            SettingsEntry::BoardWidth | SettingsEntry::BoardHeight if settings.level.is_some() => {
+                return false
+            }
+            SettingsEntry::BoardWidth => {
+                changed.board_width = step_by(
+                    changed.board_width as u32,
+                    MIN_BOARD_SIZE as u32,
+                    MAX_BOARD_SIZE as u32,
+                ) as u16;
+            }
+            SettingsEntry::BoardHeight => {
+                changed.board_height = step_by(
+                    changed.board_height as u32,
+                    MIN_BOARD_SIZE as u32,
+                    MAX_BOARD_SIZE as u32,
+                ) as u16;
+            }
+            SettingsEntry::StartSize => {
+                changed.snake_start_size = step_by(changed.snake_start_size, 0, u32::MAX);
+            }
+            SettingsEntry::Countdown => {
+                changed.countdown = step_by(changed.countdown, 0, MAX_COUNTDOWN);
+            }
+            SettingsEntry::Back => return false,
+        }
+
+        if changed == *settings || changed.validate().is_err() {
+            return false;
+        }
+
+        *settings = changed;
+        true
+    }
+}
+
+/// Changes the settings of the next game
+pub struct SettingsScreen {
+    pub settings: Settings,
+    pub menu: LazyDrawable<Menu<SettingsEntry>>,
+}
+
+impl SettingsScreen {
+    pub fn try_new(ctx: &mut Context, settings: Settings) -> tetra::Result<SettingsScreen> {
+        let items = SettingsEntry::ALL
+            .iter()
+            .map(|entry| (*entry, entry.label(&settings)))
+            .collect();
+        let mut menu = Menu::new(i18n::tr("settings"), items);
+        menu.hint = Some(i18n::tr("settings-hint"));
+
+        Ok(SettingsScreen {
+            settings,
+            menu: LazyDrawable::new(
+                menu,
+                graphics::Canvas::new(ctx, WINDOW_WIDTH as i32, WINDOW_HEIGHT as i32)?,
+                None,
+            ),
+        })
+    }
+
+    /// Shows the settings that the next game will use
+    pub fn use_settings(&mut self, settings: Settings) {
+        self.settings = settings;
+        self.update_labels();
+    }
+
+    fn update_labels(&mut self) {
+        for entry in SettingsEntry::ALL.iter() {
+            let label = entry.label(&self.settings);
+            self.menu.set_label(*entry, label);
+        }
+    }
+
+    fn adjust(&mut self, entry: SettingsEntry, step: i32) {
+        if entry.adjust(&mut self.settings, step) {
+            self.update_labels();
+        }
+    }
+}
+
+impl Drawable for SettingsScreen {
+    fn draw(&mut self, ctx: &mut Context) -> tetra::Result {
+        graphics::clear(ctx, Color::rgba(0, 0, 0, 1.0).into());
+
+        self.menu.draw(ctx)?;
+
+        Ok(())
+    }
+}
+
+impl Updatable for SettingsScreen {}
+
+impl EventHandler for SettingsScreen {
+    fn event(&mut self, screen: &mut CurrentScreen, event: Event) {
+        let input = match MenuInput::from_event(&event) {
+            Some(input) => input,
+            None => return,
+        };
+
+        match self.menu.handle_input(input) {
+            Some(MenuChoice::Select(SettingsEntry::Back)) | Some(MenuChoice::Back) => {
+                screen.go_back();
+            }
+            // Clicks and 'Enter' step through the values
+            Some(MenuChoice::Select(entry)) => self.adjust(entry, 1),
+            Some(MenuChoice::Adjust(entry, step)) => self.adjust(entry, step),
+            None => {}
+        }
+    }
+}
+
+impl Enterable for SettingsScreen {
+    fn enter(&mut self, _screen: &mut CurrentScreen) {
+        self.menu.select(SettingsEntry::Speed);
+    }
+}
+
+impl Screen for SettingsScreen {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::Level;
+
+    #[test]
+    fn test_adjust_within_bounds() {
+        let mut settings = Settings::default();
+
+        assert!(SettingsEntry::Speed.adjust(&mut settings, 1));
+        assert_eq!(settings.speed, 3.5);
+        assert!(SettingsEntry::Apples.adjust(&mut settings, 1));
+        assert_eq!(settings.apple_count, 2);
+
+        settings.apple_count = 1;
+        assert!(!SettingsEntry::Apples.adjust(&mut settings, -1));
+        assert_eq!(settings.apple_count, 1);
+
+        settings.board_width = MIN_BOARD_SIZE;
+        assert!(!SettingsEntry::BoardWidth.adjust(&mut settings, -1));
+        assert!(SettingsEntry::BoardWidth.adjust(&mut settings, 1));
+        assert_eq!(settings.board_width, MIN_BOARD_SIZE + 1);
+
+        settings.countdown = 0;
+        assert!(!SettingsEntry::Countdown.adjust(&mut settings, -1));
+        assert!(SettingsEntry::Countdown.adjust(&mut settings, 1));
+        assert_eq!(settings.countdown, 1);
+    }
+
+    #[test]
+    fn test_adjust_keeps_settings_playable() {
+        let mut settings = Settings {
+            board_width: 5,
+            board_height: 4,
+            snake_start_size: 19,
+            ..Settings::default()
+        };
+
+        // The snake would no longer fit onto the board
+        assert!(!SettingsEntry::StartSize.adjust(&mut settings, 1));
+        assert!(!SettingsEntry::BoardWidth.adjust(&mut settings, -1));
+        assert_eq!((settings.board_width, settings.snake_start_size), (5, 19));
+
+        settings.use_level(Level::parse("box", "....\n.S..\n....\n....").unwrap());
+        assert!(!SettingsEntry::BoardWidth.adjust(&mut settings, 1));
+        assert_eq!(settings.board_width, 4);
+    }
+}
@@ -1,24 +1,224 @@
-use super::{Drawable, EventHandler, Screen, Updatable};
-use crate::alert::Alert;
+use super::mode_selector::ModeSelector;
+use super::{Drawable, Enterable, EventHandler, Screen, Updatable};
 use crate::color::Color;
+use crate::daily::{DailyChallenge, DailyResults};
+use crate::game::Level;
+use crate::game_mode::GameMode;
+use crate::i18n;
 use crate::lazy_drawable::LazyDrawable;
+use crate::menu::{Menu, MenuChoice, MenuInput};
+use crate::savegame::{LoadError, SaveGame};
+use crate::settings::Settings;
+use crate::storage;
 use crate::{CurrentScreen, ScreenName, WINDOW_HEIGHT, WINDOW_WIDTH};
-use tetra::input::{Key, MouseButton};
+use tetra::input::Key;
 use tetra::{graphics, Context, Event};
 
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum MainMenuItem {
+    Continue,
+    Play,
+    Daily,
+    Mode,
+    Level,
+    Leaderboard,
+    Statistics,
+    Settings,
+    Quit,
+}
+
 pub struct StartScreen {
-    pub intro_alert: LazyDrawable<Alert>,
+    pub menu: LazyDrawable<Menu<MainMenuItem>>,
+    mode_selector: ModeSelector,
+    levels: Vec<Level>,
+    /// Index of the selected level, where 0 is the board without walls
+    level_index: usize,
+    has_saved_game: bool,
+    /// Score of today's daily challenge, once it has been played
+    daily_score: Option<u32>,
+    daily_requested: bool,
 }
 
 impl StartScreen {
-    pub fn try_new(ctx: &mut Context) -> tetra::Result<StartScreen> {
-        Ok(StartScreen {
-            intro_alert: LazyDrawable::new(
-                Alert::try_new("Snake", "Press 'Space' to start")?,
+    /// Offers the levels in the data directory, along with the level that
+    /// the game has been started with
+    pub fn try_new(
+        ctx: &mut Context,
+        mode: GameMode,
+        level: Option<Level>,
+    ) -> tetra::Result<StartScreen> {
+        let mut levels = Level::load_dir(&storage::data_dir().join("levels"));
+        let level_index = match level {
+            Some(level) => match levels.iter().position(|other| *other == level) {
+                Some(index) => index + 1,
+                None => {
+                    levels.insert(0, level);
+                    1
+                }
+            },
+            None => 0,
+        };
+
+        let mut screen = StartScreen {
+            menu: LazyDrawable::new(
+                Menu::new(i18n::tr("app-title"), Vec::new()),
                 graphics::Canvas::new(ctx, WINDOW_WIDTH as i32, WINDOW_HEIGHT as i32)?,
                 None,
             ),
-        })
+            mode_selector: ModeSelector::new(mode),
+            levels,
+            level_index,
+            has_saved_game: false,
+            daily_score: None,
+            daily_requested: false,
+        };
+
+        screen.check_daily();
+        screen.check_saved_game();
+
+        Ok(screen)
+    }
+
+    /// Offers to continue the saved game if there is one that can be loaded
+    fn check_saved_game(&mut self) {
+        self.has_saved_game = match SaveGame::load() {
+            Ok(_) => true,
+            Err(LoadError::Missing) => false,
+            Err(err) => {
+                eprintln!("Unable to load the saved game: {}", err);
+                false
+            }
+        };
+
+        self.update_menu();
+    }
+
+    fn check_daily(&mut self) {
+        let challenge = DailyChallenge::today(&Settings::default());
+        self.daily_score = DailyResults::load(&storage::data_dir())
+            .get(&challenge)
+            .map(|result| result.score);
+    }
+
+    fn update_menu(&mut self) {
+        let items = [
+            MainMenuItem::Continue,
+            MainMenuItem::Play,
+            MainMenuItem::Daily,
+            MainMenuItem::Mode,
+            MainMenuItem::Level,
+            MainMenuItem::Leaderboard,
+            MainMenuItem::Statistics,
+            MainMenuItem::Settings,
+            MainMenuItem::Quit,
+        ]
+        .iter()
+        .filter(|item| **item != MainMenuItem::Continue || self.has_saved_game)
+        .map(|item| (*item, self.label(*item)))
+        .collect();
+
+        let mut menu = Menu::new(i18n::tr("app-title"), items);
+        // Keeps the selection when coming back from another screen
+        if let Some(selected) = self.menu.selected() {
+            menu.select(selected);
+        }
+        menu.hint = Some(i18n::tr(if self.has_saved_game {
+            "start-hint-continue"
+        } else {
+            "start-hint"
+        }));
+        self.menu.set(menu);
+    }
+
+    fn label(&self, item: MainMenuItem) -> String {
+        match item {
+            MainMenuItem::Continue => i18n::tr("menu-continue"),
+            MainMenuItem::Play => i18n::tr("menu-play"),
+            MainMenuItem::Daily => match self.daily_score {
+                Some(score) => i18n::tr_with("menu-daily-played", &[("score", &score)]),
+                None => i18n::tr("menu-daily"),
+            },
+            MainMenuItem::Mode => format!(
+                "{}:  < {} >",
+                i18n::tr("menu-mode"),
+                self.mode_selector.selected()
+            ),
+            MainMenuItem::Level => format!(
+                "{}:  < {} >",
+                i18n::tr("menu-level"),
+                match self.selected_level() {
+                    Some(level) => level.name.clone(),
+                    None => i18n::tr("level-none"),
+                }
+            ),
+            MainMenuItem::Leaderboard => i18n::tr("leaderboard"),
+            MainMenuItem::Statistics => i18n::tr("statistics"),
+            MainMenuItem::Settings => i18n::tr("menu-settings"),
+            MainMenuItem::Quit => i18n::tr("menu-quit-game"),
+        }
+    }
+
+    fn selected_level(&self) -> Option<&Level> {
+        self.level_index
+            .checked_sub(1)
+            .and_then(|index| self.levels.get(index))
+    }
+
+    /// Puts the selected level into the settings. Returns whether they have
+    /// changed.
+    pub fn apply_level(&self, settings: &mut Settings) -> bool {
+        let level = self.selected_level();
+        if settings.level.as_ref() == level {
+            return false;
+        }
+
+        match level {
+            Some(level) => settings.use_level(level.clone()),
+            None => settings.clear_level(),
+        }
+
+        true
+    }
+
+    /// Selects the next mode or level in the direction of the step
+    fn adjust(&mut self, item: MainMenuItem, step: i32) {
+        match item {
+            MainMenuItem::Mode if step < 0 => self.mode_selector.select_previous(),
+            MainMenuItem::Mode => self.mode_selector.select_next(),
+            MainMenuItem::Level => {
+                let count = self.levels.len() + 1;
+                self.level_index = if step < 0 {
+                    (self.level_index + count - 1) % count
+                } else {
+                    (self.level_index + 1) % count
+                };
+            }
+            _ => return,
+        }
+
+        let label = self.label(item);
+        self.menu.set_label(item, label);
+    }
+
+    fn start_game(&self, screen: &mut CurrentScreen) {
+        screen.use_game_mode(self.mode_selector.selected());
+        screen.use_screen(ScreenName::Game);
+    }
+
+    /// Whether the daily challenge has been chosen, which is only asked once
+    pub fn take_daily_request(&mut self) -> bool {
+        std::mem::replace(&mut self.daily_requested, false)
+    }
+
+    fn start_daily(&mut self, screen: &mut CurrentScreen) {
+        self.daily_requested = true;
+        screen.use_game_mode(GameMode::Daily);
+        screen.use_screen(ScreenName::Game);
+    }
+
+    fn continue_game(&self, screen: &mut CurrentScreen) {
+        screen.request_saved_game();
+        screen.use_screen(ScreenName::Game);
     }
 }
 
@@ -26,7 +226,7 @@ impl Drawable for StartScreen {
     fn draw(&mut self, ctx: &mut Context) -> tetra::Result {
         graphics::clear(ctx, Color::rgba(0, 0, 0, 1.0).into());
 
-        self.intro_alert.draw(ctx)?;
+        self.menu.draw(ctx)?;
 
         Ok(())
     }
@@ -41,18 +241,44 @@ impl Updatable for StartScreen {
 impl EventHandler for StartScreen {
     fn event(&mut self, screen: &mut CurrentScreen, event: Event) {
         match event {
-            Event::KeyPressed {
-                key: Key::Space | Key::Enter | Key::NumPadEnter,
-            } => {
-                screen.use_screen(ScreenName::Game);
+            Event::KeyPressed { key: Key::C } if self.has_saved_game => {
+                self.continue_game(screen);
+                return;
             }
-            Event::MouseButtonPressed {
-                button: MouseButton::Left,
-            } => {
-                screen.use_screen(ScreenName::Game);
+            Event::KeyPressed { key: Key::Tab } => {
+                screen.use_screen(ScreenName::Achievements);
+                return;
             }
             _ => {}
+        }
+
+        let input = match MenuInput::from_event(&event) {
+            Some(input) => input,
+            None => return,
         };
+
+        match self.menu.handle_input(input) {
+            Some(MenuChoice::Select(item)) => match item {
+                MainMenuItem::Continue => self.continue_game(screen),
+                MainMenuItem::Play => self.start_game(screen),
+                MainMenuItem::Daily => self.start_daily(screen),
+                // Mode and level step through their options
+                MainMenuItem::Mode | MainMenuItem::Level => self.adjust(item, 1),
+                MainMenuItem::Leaderboard => screen.use_screen(ScreenName::Leaderboard),
+                MainMenuItem::Statistics => screen.use_screen(ScreenName::Statistics),
+                MainMenuItem::Settings => screen.use_screen(ScreenName::Settings),
+                MainMenuItem::Quit => screen.request_quit(),
+            },
+            Some(MenuChoice::Adjust(item, step)) => self.adjust(item, step),
+            Some(MenuChoice::Back) | None => {}
+        }
+    }
+}
+
+impl Enterable for StartScreen {
+    fn enter(&mut self, _screen: &mut CurrentScreen) {
+        self.check_daily();
+        self.check_saved_game();
     }
 }
 
@@ -0,0 +1,184 @@
+use super::{Drawable, Enterable, EventHandler, Screen, Updatable};
+use crate::alert::Alert;
+use crate::assets;
+use crate::color::Color;
+use crate::config;
+use crate::i18n;
+use crate::lazy_drawable::LazyDrawable;
+use crate::statistics::Statistics;
+use crate::storage;
+use crate::{CurrentScreen, ScreenName, WINDOW_HEIGHT, WINDOW_WIDTH};
+use tetra::graphics::text::Text;
+use tetra::graphics::DrawParams;
+use tetra::input::{Key, MouseButton};
+use tetra::math::Vec2;
+use tetra::{graphics, Context, Event};
+
+pub struct StatisticsList {
+    pub statistics: Statistics,
+    pub status: Option<String>,
+}
+
+impl StatisticsList {
+    pub fn new() -> StatisticsList {
+        StatisticsList {
+            statistics: Statistics::default(),
+            status: None,
+        }
+    }
+
+    fn entries(&self) -> Vec<(String, String)> {
+        let lifetime = self.statistics.lifetime();
+
+        vec![
+            (i18n::tr("games-played"), lifetime.games_played.to_string()),
+            (i18n::tr("victories"), lifetime.victories.to_string()),
+            (i18n::tr("best-score"), lifetime.best_score.to_string()),
+            (
+                i18n::tr("average-score"),
+                format!("{:.1}", lifetime.average_score()),
+            ),
+            (i18n::tr("apples-eaten"), lifetime.total_apples.to_string()),
+            (i18n::tr("turns-made"), lifetime.total_turns.to_string()),
+            (
+                i18n::tr("time-played"),
+                format!(
+                    "{}:{:02}",
+                    lifetime.total_seconds as u64 / 60,
+                    lifetime.total_seconds as u64 % 60
+                ),
+            ),
+            (
+                i18n::tr("ticks-between-apples"),
+                lifetime
+                    .average_apple_interval
+                    .map(|interval| format!("{:.1}", interval))
+                    .unwrap_or_else(|| "-".to_string()),
+            ),
+            (
+                i18n::tr("deaths-by-wall"),
+                lifetime.deaths_by_wall.to_string(),
+            ),
+            (
+                i18n::tr("deaths-by-tail"),
+                lifetime.deaths_by_tail.to_string(),
+            ),
+        ]
+    }
+}
+
+impl Drawable for StatisticsList {
+    fn draw(&mut self, ctx: &mut Context) -> tetra::Result {
+        let title_font = assets::font(ctx, 40.0)?;
+        Alert::draw_text(ctx, i18n::tr("statistics"), title_font, 30.0)?;
+
+        let entry_font = assets::font(ctx, 18.0)?;
+        for (index, (label, value)) in self.entries().into_iter().enumerate() {
+            let y = 100.0 + index as f32 * 30.0;
+
+            let label = Text::new(label, entry_font.clone());
+            graphics::draw(
+                ctx,
+                &label,
+                DrawParams::new()
+                    .color(config::HUD_LABEL_COLOR.as_tetra())
+                    .position(Vec2::new(160.0, y)),
+            );
+
+            let value = Text::new(value, entry_font.clone());
+            graphics::draw(
+                ctx,
+                &value,
+                DrawParams::new()
+                    .color(config::HUD_VALUE_COLOR.as_tetra())
+                    .position(Vec2::new(460.0, y)),
+            );
+        }
+
+        let hint_font = assets::font(ctx, 16.0)?;
+        if let Some(status) = &self.status {
+            Alert::draw_text(ctx, status, hint_font.clone(), (WINDOW_HEIGHT - 70) as f32)?;
+        }
+
+        Alert::draw_text(
+            ctx,
+            i18n::tr("statistics-hint"),
+            hint_font.clone(),
+            (WINDOW_HEIGHT - 40) as f32,
+        )?;
+
+        Ok(())
+    }
+}
+
+pub struct StatisticsScreen {
+    pub list: LazyDrawable<StatisticsList>,
+}
+
+impl StatisticsScreen {
+    pub fn try_new(ctx: &mut Context) -> tetra::Result<StatisticsScreen> {
+        Ok(StatisticsScreen {
+            list: LazyDrawable::new(
+                StatisticsList::new(),
+                graphics::Canvas::new(ctx, WINDOW_WIDTH as i32, WINDOW_HEIGHT as i32)?,
+                None,
+            ),
+        })
+    }
+
+    fn export(&mut self, file_name: &str, content: String) {
+        self.list.status = Some(
+            match storage::write(&storage::data_dir(), file_name, &content) {
+                Ok(path) => i18n::tr_with("statistics-exported", &[("path", &path.display())]),
+                Err(err) => i18n::tr_with("statistics-export-failed", &[("error", &err)]),
+            },
+        );
+    }
+}
+
+impl Drawable for StatisticsScreen {
+    fn draw(&mut self, ctx: &mut Context) -> tetra::Result {
+        graphics::clear(ctx, Color::rgba(0, 0, 0, 1.0).into());
+
+        self.list.draw(ctx)?;
+
+        Ok(())
+    }
+}
+
+impl Updatable for StatisticsScreen {}
+
+impl EventHandler for StatisticsScreen {
+    fn event(&mut self, screen: &mut CurrentScreen, event: Event) {
+        match event {
+            Event::KeyPressed { key: Key::C } => {
+                let csv = self.list.statistics.to_csv();
+                self.export("statistics-export.csv", csv);
+            }
+            Event::KeyPressed { key: Key::J } => {
+                let json = self.list.statistics.to_json();
+                self.export("statistics-export.json", json);
+            }
+            Event::KeyPressed {
+                key: Key::Escape | Key::Backspace | Key::Space | Key::Enter | Key::NumPadEnter,
+            } => {
+                screen.use_screen(ScreenName::Start);
+            }
+            Event::MouseButtonPressed {
+                button: MouseButton::Left,
+            } => {
+                screen.use_screen(ScreenName::Start);
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Enterable for StatisticsScreen {
+    fn enter(&mut self, _screen: &mut CurrentScreen) {
+        self.list.statistics = Statistics::load(&storage::data_dir());
+        self.list.status = None;
+    }
+}
+
+impl Screen for StatisticsScreen {}
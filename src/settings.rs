@@ -0,0 +1,409 @@
+use crate::config;
+use crate::game::Level;
+use crate::i18n;
+use crate::item::ItemTable;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::{error, fmt, fs};
+use vek::Vec2;
+
+pub const MIN_BOARD_SIZE: u16 = 4;
+pub const MAX_BOARD_SIZE: u16 = 100;
+/// Ticks per second above which the snake would move more than once per update
+pub const MAX_SPEED: f64 = config::UPDATES_PER_SECOND;
+pub const MAX_COUNTDOWN: u32 = 10;
+
+/// Missing fields keep their default, so that files only need to list what
+/// they change
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct Settings {
+    pub apple_count: u32,
+    pub snake_start_size: u32,
+    pub item_weights: ItemTable,
+    pub item_durations: ItemTable,
+    pub board_width: u16,
+    pub board_height: u16,
+    /// Speed of the snake in tiles per second without any effects
+    pub speed: f64,
+    /// Seed of the random number generator, a random one is chosen for every
+    /// game if there is none
+    pub seed: Option<u64>,
+    /// Walls on the board, whose size matches the board size
+    pub level: Option<Level>,
+    /// Language of all texts, does not influence the score
+    pub language: String,
+    /// Seconds counted down before the game starts or continues, 0 starts
+    /// right away. Does not influence the score.
+    pub countdown: u32,
+}
+
+#[derive(Debug)]
+pub enum SettingsError {
+    Invalid {
+        name: String,
+        value: String,
+        reason: String,
+    },
+    File {
+        path: String,
+        reason: String,
+    },
+}
+
+impl SettingsError {
+    pub fn invalid<N: Into<String>, V: ToString, R: ToString>(
+        name: N,
+        value: V,
+        reason: R,
+    ) -> SettingsError {
+        SettingsError::Invalid {
+            name: name.into(),
+            value: value.to_string(),
+            reason: reason.to_string(),
+        }
+    }
+}
+
+/// Reads an environment variable if it is set to a non-empty value
+fn env_var(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|value| !value.is_empty())
+}
+
+fn parse_env<T: std::str::FromStr>(name: &str) -> Result<Option<T>, SettingsError>
+where
+    T::Err: fmt::Display,
+{
+    env_var(name)
+        .map(|value| {
+            value
+                .parse::<T>()
+                .map_err(|err| SettingsError::invalid(name, &value, err))
+        })
+        .transpose()
+}
+
+impl Settings {
+    /// Default settings in the language of the system, changed by the
+    /// environment variables
+    pub fn from_env() -> Result<Settings, SettingsError> {
+        Settings {
+            language: i18n::detect_language(),
+            ..Settings::default()
+        }
+        .with_env()
+    }
+
+    /// Settings of a JSON file, changed by the environment variables
+    pub fn from_file(path: &Path) -> Result<Settings, SettingsError> {
+        let file_error = |reason: &dyn fmt::Display| SettingsError::File {
+            path: path.display().to_string(),
+            reason: reason.to_string(),
+        };
+
+        let content = fs::read_to_string(path).map_err(|err| file_error(&err))?;
+        let value: serde_json::Value =
+            serde_json::from_str(&content).map_err(|err| file_error(&err))?;
+        let has_language = value.get("language").is_some();
+        let mut settings: Settings =
+            serde_json::from_value(value).map_err(|err| file_error(&err))?;
+        if !has_language {
+            settings.language = i18n::detect_language();
+        }
+
+        settings.with_env()
+    }
+
+    /// Applies the environment variables that are set
+    pub fn with_env(mut self) -> Result<Settings, SettingsError> {
+        if let Some(apple_count) = parse_env("APPLE_COUNT")? {
+            self.apple_count = apple_count;
+        }
+        if let Some(snake_start_size) = parse_env("SNAKE_START_SIZE")? {
+            self.snake_start_size = snake_start_size;
+        }
+        if let Some(weights) = env_var("ITEM_WEIGHTS") {
+            self.item_weights = ItemTable::parse_with_defaults(&weights, &self.item_weights)
+                .map_err(|err| SettingsError::invalid("ITEM_WEIGHTS", &weights, err))?;
+        }
+        if let Some(durations) = env_var("ITEM_DURATIONS") {
+            self.item_durations = ItemTable::parse_with_defaults(&durations, &self.item_durations)
+                .map_err(|err| SettingsError::invalid("ITEM_DURATIONS", &durations, err))?;
+        }
+        if let Some(language) = env_var("SNAKE_LANGUAGE") {
+            self.language = language;
+        }
+        if let Some(countdown) = parse_env("SNAKE_COUNTDOWN")? {
+            self.countdown = countdown;
+        }
+
+        Ok(self)
+    }
+
+    /// Uses the level and its board size
+    pub fn use_level(&mut self, level: Level) {
+        self.board_width = level.width;
+        self.board_height = level.height;
+        self.level = Some(level);
+    }
+
+    /// Removes the level and goes back to the default board size
+    pub fn clear_level(&mut self) {
+        if self.level.take().is_some() {
+            self.board_width = config::TILE_COUNT_X;
+            self.board_height = config::TILE_COUNT_Y;
+        }
+    }
+
+    pub fn walls(&self) -> &[Vec2<i32>] {
+        match &self.level {
+            Some(level) => &level.walls,
+            None => &[],
+        }
+    }
+
+    /// Position of the snake's head at the start of a game
+    pub fn start_position(&self) -> Vec2<i32> {
+        if let Some(start) = self.level.as_ref().and_then(|level| level.start) {
+            return start;
+        }
+
+        Vec2::new(
+            ((self.board_width - 1) as f32 * 0.5).floor() as i32,
+            ((self.board_height - 1) as f32 * 0.65).floor() as i32,
+        )
+    }
+
+    /// Checks that a game can be played with these settings
+    pub fn validate(&self) -> Result<(), SettingsError> {
+        let board_sizes = MIN_BOARD_SIZE..=MAX_BOARD_SIZE;
+        if !board_sizes.contains(&self.board_width) || !board_sizes.contains(&self.board_height) {
+            return Err(SettingsError::invalid(
+                "board",
+                format!("{}x{}", self.board_width, self.board_height),
+                format!(
+                    "width and height need to be between {} and {}",
+                    MIN_BOARD_SIZE, MAX_BOARD_SIZE
+                ),
+            ));
+        }
+
+        if !(self.speed > 0.0 && self.speed <= MAX_SPEED) {
+            return Err(SettingsError::invalid(
+                "speed",
+                self.speed,
+                format!("needs to be above 0 and at most {}", MAX_SPEED),
+            ));
+        }
+
+        if self.countdown > MAX_COUNTDOWN {
+            return Err(SettingsError::invalid(
+                "countdown",
+                self.countdown,
+                format!("needs to be at most {} seconds", MAX_COUNTDOWN),
+            ));
+        }
+
+        if self.apple_count == 0 {
+            return Err(SettingsError::invalid(
+                "apples",
+                self.apple_count,
+                "at least one apple is needed",
+            ));
+        }
+
+        let cells = self.board_width as u32 * self.board_height as u32;
+        if self.snake_start_size >= cells {
+            return Err(SettingsError::invalid(
+                "start size",
+                self.snake_start_size,
+                "the snake needs to fit onto the board",
+            ));
+        }
+
+        if let Some(level) = &self.level {
+            if (level.width, level.height) != (self.board_width, self.board_height) {
+                return Err(SettingsError::invalid(
+                    "board",
+                    format!("{}x{}", self.board_width, self.board_height),
+                    format!(
+                        "the level '{}' is {}x{}",
+                        level.name, level.width, level.height
+                    ),
+                ));
+            }
+
+            if level.walls.contains(&self.start_position()) {
+                return Err(SettingsError::invalid(
+                    "level",
+                    &level.name,
+                    "the snake starts on a wall, mark the start with 'S'",
+                ));
+            }
+
+            // The tail grows straight down from the head at the start and
+            // may leave the board, but must not run through a wall
+            let start = self.start_position();
+            let tail_end = start.y as i64 + self.snake_start_size as i64;
+            if level
+                .walls
+                .iter()
+                .any(|wall| wall.x == start.x && wall.y > start.y && wall.y as i64 <= tail_end)
+            {
+                return Err(SettingsError::invalid(
+                    "start size",
+                    self.snake_start_size,
+                    format!("the tail crosses a wall of the level '{}'", level.name),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Identifies the settings that influence the score, so that scores are
+    /// only ever compared against games played under the same conditions.
+    pub fn key(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            apple_count: 1,
+            snake_start_size: 0,
+            item_weights: config::ITEM_SPAWN_WEIGHTS,
+            item_durations: config::ITEM_DURATIONS,
+            board_width: config::TILE_COUNT_X,
+            board_height: config::TILE_COUNT_Y,
+            speed: config::SNAKE_SPEED,
+            seed: None,
+            level: None,
+            language: i18n::DEFAULT_LANGUAGE.to_string(),
+            countdown: config::COUNTDOWN_SECONDS,
+        }
+    }
+}
+
+impl fmt::Display for Settings {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "apples={},start-size={}",
+            self.apple_count, self.snake_start_size
+        )?;
+
+        // Only listed when changed to keep the keys of existing scores intact
+        if self.item_weights != config::ITEM_SPAWN_WEIGHTS {
+            write!(f, ",weights=[{}]", self.item_weights)?;
+        }
+        if self.item_durations != config::ITEM_DURATIONS {
+            write!(f, ",durations=[{}]", self.item_durations)?;
+        }
+        if (self.board_width, self.board_height) != (config::TILE_COUNT_X, config::TILE_COUNT_Y) {
+            write!(f, ",board={}x{}", self.board_width, self.board_height)?;
+        }
+        if self.speed != config::SNAKE_SPEED {
+            write!(f, ",speed={}", self.speed)?;
+        }
+        if let Some(level) = &self.level {
+            write!(f, ",level={}", level.name)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for SettingsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SettingsError::Invalid {
+                name,
+                value,
+                reason,
+            } => write!(f, "invalid {} '{}': {}", name, value, reason),
+            SettingsError::File { path, reason } => {
+                write!(f, "unable to read settings from '{}': {}", path, reason)
+            }
+        }
+    }
+}
+
+impl error::Error for SettingsError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_lists_changes() {
+        assert_eq!(Settings::default().key(), "apples=1,start-size=0");
+
+        let mut settings = Settings {
+            board_width: 20,
+            board_height: 12,
+            speed: 4.5,
+            seed: Some(7),
+            ..Settings::default()
+        };
+        assert_eq!(
+            settings.key(),
+            "apples=1,start-size=0,board=20x12,speed=4.5"
+        );
+
+        settings.use_level(Level::parse("box", "#####\n#...#\n#...#\n#####").unwrap());
+        assert_eq!(
+            settings.key(),
+            "apples=1,start-size=0,board=5x4,speed=4.5,level=box"
+        );
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(Settings::default().validate().is_ok());
+
+        let invalid = [
+            Settings {
+                board_width: 2,
+                ..Settings::default()
+            },
+            Settings {
+                speed: 0.0,
+                ..Settings::default()
+            },
+            Settings {
+                apple_count: 0,
+                ..Settings::default()
+            },
+            Settings {
+                snake_start_size: 15 * 9,
+                ..Settings::default()
+            },
+            Settings {
+                countdown: MAX_COUNTDOWN + 1,
+                ..Settings::default()
+            },
+        ];
+        for settings in invalid.iter() {
+            assert!(settings.validate().is_err(), "{:?}", settings);
+        }
+    }
+
+    #[test]
+    fn test_validate_start_size() {
+        // The tail may run off the board below the head
+        let mut settings = Settings {
+            snake_start_size: 8,
+            ..Settings::default()
+        };
+        assert!(settings.validate().is_ok());
+
+        let level = Level::parse("pillar", "#####\n#.S.#\n#...#\n#.#.#\n#####").unwrap();
+        settings.use_level(level);
+        settings.snake_start_size = 1;
+        assert!(settings.validate().is_ok());
+        settings.snake_start_size = 2;
+        assert!(settings.validate().is_err());
+    }
+}
@@ -0,0 +1,380 @@
+use crate::game_mode::{EndReason, GameMode, GameProgress};
+use crate::settings::Settings;
+use crate::storage;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const FILE_NAME: &str = "statistics.json";
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Copy, Clone, Debug)]
+#[serde(rename_all = "kebab-case")]
+pub enum DeathCause {
+    Wall,
+    Tail,
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Debug)]
+pub struct Death {
+    pub cause: DeathCause,
+    /// Tile the snake tried to move onto
+    pub x: i32,
+    pub y: i32,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
+pub struct GameRecord {
+    /// Unix timestamp of the start of the game
+    pub started_at: u64,
+    pub mode: String,
+    pub settings: String,
+    pub end_reason: String,
+    pub death: Option<Death>,
+    pub score: u32,
+    pub length: u32,
+    pub ticks: u64,
+    pub elapsed_seconds: f64,
+    pub turns: u32,
+    pub apples_eaten: u32,
+    /// Ticks between two eaten apples, starting with the ticks until the first one
+    pub apple_intervals: Vec<u64>,
+    /// Ticks that have been undone by rewinding, which excludes the score
+    /// from the high scores
+    #[serde(default)]
+    pub rewound_ticks: u32,
+}
+
+impl GameRecord {
+    pub fn average_apple_interval(&self) -> Option<f64> {
+        average(&self.apple_intervals)
+    }
+}
+
+/// Collects the statistics of a single game while it is played
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct GameRecorder {
+    started_at: u64,
+    mode: GameMode,
+    settings: Settings,
+    turns: u32,
+    last_apple_tick: u64,
+    apple_intervals: Vec<u64>,
+    rewound_ticks: u32,
+}
+
+impl GameRecorder {
+    pub fn new(mode: GameMode, settings: Settings) -> GameRecorder {
+        GameRecorder {
+            started_at: unix_timestamp(),
+            mode,
+            settings,
+            turns: 0,
+            last_apple_tick: 0,
+            apple_intervals: Vec::new(),
+            rewound_ticks: 0,
+        }
+    }
+
+    /// Goes back to the recorder of an earlier tick, which keeps counting
+    /// the rewound ticks
+    pub fn rewind_to(&mut self, earlier: GameRecorder) {
+        let rewound_ticks = self.rewound_ticks + 1;
+        *self = earlier;
+        self.rewound_ticks = rewound_ticks;
+    }
+
+    pub fn rewound_ticks(&self) -> u32 {
+        self.rewound_ticks
+    }
+
+    pub fn record_turn(&mut self) {
+        self.turns += 1;
+    }
+
+    pub fn record_apple(&mut self, tick: u64) {
+        self.apple_intervals
+            .push(tick.saturating_sub(self.last_apple_tick));
+        self.last_apple_tick = tick;
+    }
+
+    pub fn finish(
+        &self,
+        reason: EndReason,
+        progress: &GameProgress,
+        death: Option<Death>,
+    ) -> GameRecord {
+        GameRecord {
+            started_at: self.started_at,
+            mode: self.mode.id(),
+            settings: self.settings.key(),
+            end_reason: reason.name().to_string(),
+            death,
+            score: progress.score,
+            length: progress.length,
+            ticks: progress.ticks,
+            elapsed_seconds: progress.elapsed_seconds,
+            turns: self.turns,
+            apples_eaten: self.apple_intervals.len() as u32,
+            apple_intervals: self.apple_intervals.clone(),
+            rewound_ticks: self.rewound_ticks,
+        }
+    }
+}
+
+/// Statistics aggregated over all games ever played
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LifetimeStatistics {
+    pub games_played: u32,
+    pub victories: u32,
+    pub best_score: u32,
+    pub total_score: u64,
+    pub total_ticks: u64,
+    pub total_seconds: f64,
+    pub total_turns: u64,
+    pub total_apples: u64,
+    pub deaths_by_wall: u32,
+    pub deaths_by_tail: u32,
+    pub average_apple_interval: Option<f64>,
+}
+
+impl LifetimeStatistics {
+    pub fn average_score(&self) -> f64 {
+        if self.games_played == 0 {
+            return 0.0;
+        }
+
+        self.total_score as f64 / self.games_played as f64
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+pub struct Statistics {
+    pub games: Vec<GameRecord>,
+}
+
+impl Statistics {
+    pub fn load(dir: &Path) -> Statistics {
+        storage::load(dir, FILE_NAME).unwrap_or_else(|err| {
+            eprintln!("Unable to load statistics: {}", err);
+            Statistics::default()
+        })
+    }
+
+    pub fn save(&self, dir: &Path) {
+        if let Err(err) = storage::save(dir, FILE_NAME, self) {
+            eprintln!("Unable to save statistics: {}", err);
+        }
+    }
+
+    /// Appends a game to the statistics file
+    pub fn record(dir: &Path, game: GameRecord) {
+        let mut statistics = Statistics::load(dir);
+        statistics.games.push(game);
+        statistics.save(dir);
+    }
+
+    pub fn lifetime(&self) -> LifetimeStatistics {
+        let mut lifetime = LifetimeStatistics::default();
+        let mut apple_intervals = Vec::new();
+
+        for game in self.games.iter() {
+            lifetime.games_played += 1;
+            if game.end_reason == EndReason::Victory.name() {
+                lifetime.victories += 1;
+            }
+            lifetime.best_score = lifetime.best_score.max(game.score);
+            lifetime.total_score += game.score as u64;
+            lifetime.total_ticks += game.ticks;
+            lifetime.total_seconds += game.elapsed_seconds;
+            lifetime.total_turns += game.turns as u64;
+            lifetime.total_apples += game.apples_eaten as u64;
+            match game.death.as_ref().map(|death| death.cause) {
+                Some(DeathCause::Wall) => lifetime.deaths_by_wall += 1,
+                Some(DeathCause::Tail) => lifetime.deaths_by_tail += 1,
+                None => {}
+            }
+            apple_intervals.extend(game.apple_intervals.iter().cloned());
+        }
+
+        lifetime.average_apple_interval = average(&apple_intervals);
+
+        lifetime
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(&self.games).expect("Unable to serialize statistics")
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from(
+            "started_at,mode,settings,end_reason,death_cause,death_x,death_y,score,length,\
+             ticks,elapsed_seconds,turns,apples_eaten,average_apple_interval\n",
+        );
+
+        for game in self.games.iter() {
+            let (death_cause, death_x, death_y) = match &game.death {
+                Some(death) => (
+                    match death.cause {
+                        DeathCause::Wall => "wall",
+                        DeathCause::Tail => "tail",
+                    },
+                    death.x.to_string(),
+                    death.y.to_string(),
+                ),
+                None => ("", String::new(), String::new()),
+            };
+
+            let fields = [
+                game.started_at.to_string(),
+                game.mode.clone(),
+                game.settings.clone(),
+                game.end_reason.clone(),
+                death_cause.to_string(),
+                death_x,
+                death_y,
+                game.score.to_string(),
+                game.length.to_string(),
+                game.ticks.to_string(),
+                format!("{:.2}", game.elapsed_seconds),
+                game.turns.to_string(),
+                game.apples_eaten.to_string(),
+                game.average_apple_interval()
+                    .map(|interval| format!("{:.2}", interval))
+                    .unwrap_or_default(),
+            ];
+
+            let fields: Vec<String> = fields.iter().map(|field| escape_csv(field)).collect();
+            csv.push_str(&fields.join(","));
+            csv.push('\n');
+        }
+
+        csv
+    }
+}
+
+fn escape_csv(field: &str) -> String {
+    if field.contains(&[',', '"', '\n'][..]) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn average(values: &[u64]) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+
+    Some(values.iter().sum::<u64>() as f64 / values.len() as f64)
+}
+
+fn unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn game(score: u32, death: Option<Death>, apple_intervals: Vec<u64>) -> GameRecord {
+        GameRecord {
+            started_at: 0,
+            mode: GameMode::Endless.id(),
+            settings: Settings::default().key(),
+            end_reason: EndReason::Crashed.name().to_string(),
+            death,
+            score,
+            length: score + 1,
+            ticks: 100,
+            elapsed_seconds: 33.3,
+            turns: 12,
+            apples_eaten: apple_intervals.len() as u32,
+            apple_intervals,
+            rewound_ticks: 0,
+        }
+    }
+
+    #[test]
+    fn test_recorder() {
+        let mut recorder = GameRecorder::new(GameMode::Endless, Settings::default());
+        recorder.record_turn();
+        recorder.record_apple(10);
+        recorder.record_turn();
+        recorder.record_apple(25);
+
+        let progress = GameProgress {
+            ticks: 30,
+            elapsed_seconds: 10.0,
+            length: 3,
+            score: 2,
+        };
+        let death = Death {
+            cause: DeathCause::Wall,
+            x: -1,
+            y: 4,
+        };
+        let record = recorder.finish(EndReason::Crashed, &progress, Some(death.clone()));
+
+        assert_eq!(record.turns, 2);
+        assert_eq!(record.apples_eaten, 2);
+        assert_eq!(record.apple_intervals, vec![10, 15]);
+        assert_eq!(record.average_apple_interval(), Some(12.5));
+        assert_eq!(record.death, Some(death));
+    }
+
+    #[test]
+    fn test_lifetime() {
+        let statistics = Statistics {
+            games: vec![
+                game(
+                    4,
+                    Some(Death {
+                        cause: DeathCause::Wall,
+                        x: 15,
+                        y: 3,
+                    }),
+                    vec![2, 4],
+                ),
+                game(
+                    8,
+                    Some(Death {
+                        cause: DeathCause::Tail,
+                        x: 5,
+                        y: 3,
+                    }),
+                    vec![6],
+                ),
+            ],
+        };
+
+        let lifetime = statistics.lifetime();
+
+        assert_eq!(lifetime.games_played, 2);
+        assert_eq!(lifetime.best_score, 8);
+        assert_eq!(lifetime.average_score(), 6.0);
+        assert_eq!(lifetime.total_apples, 3);
+        assert_eq!(lifetime.deaths_by_wall, 1);
+        assert_eq!(lifetime.deaths_by_tail, 1);
+        assert_eq!(lifetime.average_apple_interval, Some(4.0));
+    }
+
+    #[test]
+    fn test_csv() {
+        let statistics = Statistics {
+            games: vec![game(4, None, vec![2, 4])],
+        };
+
+        let csv = statistics.to_csv();
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("started_at,mode,settings,"));
+        assert_eq!(
+            lines[1],
+            "0,endless,\"apples=1,start-size=0\",crashed,,,,4,5,100,33.30,12,2,3.00"
+        );
+    }
+}
@@ -0,0 +1,76 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::{fs, io};
+
+/// Directory in which persistent game data is stored.
+///
+/// Can be overridden with the `SNAKE_DATA_DIR` environment variable.
+pub fn data_dir() -> PathBuf {
+    if let Some(dir) = std::env::var_os("SNAKE_DATA_DIR") {
+        return PathBuf::from(dir);
+    }
+
+    if let Some(dir) = std::env::var_os("XDG_DATA_HOME") {
+        return PathBuf::from(dir).join("snake");
+    }
+
+    if let Some(dir) = std::env::var_os("APPDATA") {
+        return PathBuf::from(dir).join("snake");
+    }
+
+    if let Some(home) = std::env::var_os("HOME") {
+        return PathBuf::from(home)
+            .join(".local")
+            .join("share")
+            .join("snake");
+    }
+
+    PathBuf::from(".")
+}
+
+/// Loads a JSON file from the data directory, falling back to the default
+/// value if the file does not exist yet.
+pub fn load<T: DeserializeOwned + Default>(dir: &Path, file_name: &str) -> io::Result<T> {
+    let path = dir.join(file_name);
+
+    let content = match fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(T::default()),
+        Err(err) => return Err(err),
+    };
+
+    serde_json::from_str(&content).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
+pub fn save<T: Serialize>(dir: &Path, file_name: &str, value: &T) -> io::Result<()> {
+    fs::create_dir_all(dir)?;
+
+    let content = serde_json::to_string_pretty(value)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+    fs::write(dir.join(file_name), content)
+}
+
+/// Writes a plain file into the data directory and returns its path.
+pub fn write(dir: &Path, file_name: &str, content: &str) -> io::Result<PathBuf> {
+    fs::create_dir_all(dir)?;
+
+    let path = dir.join(file_name);
+    fs::write(&path, content)?;
+
+    Ok(path)
+}
+
+/// Reads a plain file from the data directory.
+pub fn read(dir: &Path, file_name: &str) -> io::Result<String> {
+    fs::read_to_string(dir.join(file_name))
+}
+
+/// Removes a file from the data directory, if it exists.
+pub fn remove(dir: &Path, file_name: &str) -> io::Result<()> {
+    match fs::remove_file(dir.join(file_name)) {
+        Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+        _ => Ok(()),
+    }
+}
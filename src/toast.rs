@@ -0,0 +1,106 @@
+use crate::assets;
+use crate::config;
+use crate::resources;
+use crate::screen::Drawable;
+use std::collections::VecDeque;
+use tetra::graphics::text::{Font, Text};
+use tetra::graphics::DrawParams;
+use tetra::math::Vec2;
+use tetra::{graphics, Context};
+
+/// Short notification that is shown on top of the screen for a while
+#[derive(Clone)]
+pub struct Toast {
+    pub heading: String,
+
+    messages: VecDeque<String>,
+    remaining_updates: u32,
+}
+
+impl Toast {
+    pub fn new<S: Into<String>>(heading: S) -> Toast {
+        Toast {
+            heading: heading.into(),
+
+            messages: VecDeque::new(),
+            remaining_updates: 0,
+        }
+    }
+
+    pub fn push<S: Into<String>>(&mut self, message: S) {
+        if self.messages.is_empty() {
+            self.remaining_updates = Toast::display_updates();
+        }
+
+        self.messages.push_back(message.into());
+    }
+
+    pub fn is_visible(&self) -> bool {
+        !self.messages.is_empty()
+    }
+
+    /// Counts down the display time of the current message and returns
+    /// whether the visible message changed.
+    pub fn advance(&mut self) -> bool {
+        if self.messages.is_empty() {
+            return false;
+        }
+
+        self.remaining_updates = self.remaining_updates.saturating_sub(1);
+        if self.remaining_updates > 0 {
+            return false;
+        }
+
+        self.messages.pop_front();
+        self.remaining_updates = Toast::display_updates();
+
+        true
+    }
+
+    fn display_updates() -> u32 {
+        (config::TOAST_DURATION * config::UPDATES_PER_SECOND) as u32
+    }
+
+    fn draw_text(ctx: &mut Context, content: &str, font: Font, y: f32) {
+        let text = Text::new(content, font);
+
+        let bounds = text
+            .get_bounds(ctx)
+            .expect("Unable to calculate bounds of text");
+
+        graphics::draw(
+            ctx,
+            &text,
+            DrawParams::new()
+                .color(config::ALERT_FONT_COLOR.as_tetra())
+                .position(Vec2::new(
+                    (crate::WINDOW_WIDTH / 2) as f32 - bounds.width / 2.0,
+                    y,
+                )),
+        );
+    }
+}
+
+impl Drawable for Toast {
+    fn draw(&mut self, ctx: &mut Context) -> tetra::Result {
+        let message = match self.messages.front() {
+            Some(message) => message,
+            None => return Ok(()),
+        };
+
+        resources::draw_rectangle(
+            ctx,
+            Vec2::new(0.0, 0.0),
+            Vec2::new(crate::WINDOW_WIDTH as f32, config::TOAST_HEIGHT as f32),
+            &config::ALERT_BACKGROUND_COLOR,
+        )?;
+
+        let heading_font = assets::font(ctx, 14.0)?;
+        Toast::draw_text(ctx, &self.heading, heading_font, 8.0);
+
+        let message_font = assets::font(ctx, 24.0)?;
+        Toast::draw_text(ctx, message, message_font, 28.0);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,465 @@
+//! Terminal frontend that plays the game with ANSI escape codes, e.g. over SSH
+
+use crate::color::Color;
+use crate::config;
+use crate::countdown::Countdown;
+use crate::game::{Direction, GameEvent, GameSession};
+use crate::game_mode::{EndReason, GameMode};
+use crate::i18n;
+use crate::play_state::{PlayState, StateMachine};
+use crate::savegame::SaveGame;
+use crate::settings::Settings;
+use std::fmt::Write;
+use std::io;
+use std::thread;
+use std::time::{Duration, Instant};
+use terminal::{Input, Terminal};
+
+mod terminal;
+
+struct TerminalGame {
+    /// Whether the mode of the first game is being chosen
+    is_choosing_mode: bool,
+    selected_mode: usize,
+    has_saved_game: bool,
+    state: PlayState,
+    countdown: Countdown,
+    session: GameSession,
+    /// Achievements that are shown below the board with their remaining updates
+    notices: Vec<(String, u32)>,
+    is_quit: bool,
+}
+
+impl TerminalGame {
+    fn new(settings: Settings) -> TerminalGame {
+        TerminalGame {
+            is_choosing_mode: true,
+            selected_mode: 0,
+            has_saved_game: SaveGame::load().is_ok(),
+            state: PlayState::Ready,
+            countdown: Countdown::new(),
+            session: GameSession::new(GameMode::default(), settings),
+            notices: Vec::new(),
+            is_quit: false,
+        }
+    }
+
+    /// Changes the state, which is only ever asked for if the transition is
+    /// allowed, as an error would garble the board in raw mode
+    fn change_state(&mut self, next: PlayState) {
+        let result = self.transition(next);
+        debug_assert!(result.is_ok(), "{:?}", result);
+    }
+
+    fn pause(&mut self) {
+        self.change_state(PlayState::Paused);
+    }
+
+    /// Counts down before the snake moves again, unless the countdown is
+    /// disabled
+    fn resume(&mut self) {
+        if self.session.game.settings.countdown > 0 {
+            self.change_state(PlayState::Countdown);
+        } else {
+            self.change_state(PlayState::Playing);
+        }
+    }
+
+    fn start(&mut self) {
+        self.is_choosing_mode = false;
+        self.session.game.mode = GameMode::PRESETS[self.selected_mode];
+        self.restart();
+    }
+
+    fn restart(&mut self) {
+        if self.state != PlayState::Ready {
+            self.change_state(PlayState::Ready);
+        }
+        self.session.restart();
+    }
+
+    /// Continues the game that was saved when the window or the terminal
+    /// was closed
+    fn continue_saved_game(&mut self) {
+        let saved_game = match SaveGame::load() {
+            Ok(saved_game) => saved_game,
+            Err(_) => {
+                self.has_saved_game = false;
+                return;
+            }
+        };
+        // A save can only be continued once
+        SaveGame::remove();
+        self.has_saved_game = false;
+
+        self.is_choosing_mode = false;
+        self.session = saved_game.session;
+        if saved_game.is_paused {
+            self.pause();
+        }
+    }
+
+    fn handle_input(&mut self, input: Input) {
+        if let Input::Interrupt | Input::Char('q') = input {
+            SaveGame::save_session(&mut self.session, self.state);
+            self.is_quit = true;
+            return;
+        }
+
+        if self.is_choosing_mode {
+            match input {
+                Input::Left => {
+                    self.selected_mode = (self.selected_mode + GameMode::PRESETS.len() - 1)
+                        % GameMode::PRESETS.len();
+                }
+                Input::Right => {
+                    self.selected_mode = (self.selected_mode + 1) % GameMode::PRESETS.len();
+                }
+                Input::Char(' ') | Input::Enter => self.start(),
+                Input::Char('c') if self.has_saved_game => self.continue_saved_game(),
+                _ => {}
+            }
+            return;
+        }
+
+        match self.state {
+            // Turns during the countdown are queued for the first tick
+            PlayState::Playing | PlayState::Countdown => {
+                let direction = match input {
+                    Input::Up | Input::Char('w') => Some(Direction::Up),
+                    Input::Down | Input::Char('s') => Some(Direction::Down),
+                    Input::Left | Input::Char('a') => Some(Direction::Left),
+                    Input::Right | Input::Char('d') => Some(Direction::Right),
+                    Input::Escape | Input::Char('p') => {
+                        self.pause();
+                        None
+                    }
+                    // Holding the key rewinds with the key repeat of the terminal
+                    Input::Backspace if self.state == PlayState::Playing => {
+                        self.session.rewind();
+                        None
+                    }
+                    _ => None,
+                };
+
+                if let Some(direction) = direction {
+                    self.session.queue_direction(direction);
+                }
+            }
+            PlayState::Paused => {
+                if let Input::Escape | Input::Enter | Input::Char('p' | ' ') = input {
+                    self.resume();
+                }
+            }
+            PlayState::GameOver | PlayState::Victory => match input {
+                Input::Enter | Input::Char('r' | ' ') => self.restart(),
+                // Crashes in practice can be rewound
+                Input::Backspace if self.session.can_rewind() => {
+                    self.session.rewind();
+                    self.change_state(PlayState::Playing);
+                }
+                _ => {}
+            },
+            PlayState::Ready => {}
+        }
+    }
+
+    fn update(&mut self) {
+        for notice in self.notices.iter_mut() {
+            notice.1 = notice.1.saturating_sub(1);
+        }
+        self.notices
+            .retain(|(_, remaining_updates)| *remaining_updates > 0);
+
+        if self.is_choosing_mode {
+            return;
+        }
+
+        if self.state == PlayState::Ready {
+            self.resume();
+        }
+        if self.state == PlayState::Countdown {
+            self.countdown.advance();
+            if self.countdown.is_finished() {
+                self.change_state(PlayState::Playing);
+            }
+            return;
+        }
+        if self.state != PlayState::Playing {
+            return;
+        }
+
+        let update = self.session.update();
+
+        let notice_updates = (config::TOAST_DURATION * config::UPDATES_PER_SECOND) as u32;
+        for achievement in update.unlocked {
+            let message = format!(
+                "{}: {}",
+                i18n::tr("achievement-unlocked"),
+                achievement.title()
+            );
+            self.notices.push((message, notice_updates));
+        }
+
+        if update
+            .events
+            .iter()
+            .any(|event| matches!(event, GameEvent::Ended { .. }))
+        {
+            if self.session.game.end == Some(EndReason::Victory) {
+                self.change_state(PlayState::Victory);
+            } else {
+                self.change_state(PlayState::GameOver);
+            }
+        }
+    }
+
+    fn cell_color(&self, x: i32, y: i32) -> Color {
+        let game = &self.session.game;
+        let occupancy = game.occupancy();
+        let position = vek::Vec2::new(x, y);
+
+        if game.snake.head == position {
+            config::SNAKE_HEAD_COLOR
+        } else if occupancy.is_free(&position) {
+            config::PLAYGROUND_GROUND_COLOR
+        } else if occupancy.is_on_snake(&position) {
+            config::SNAKE_TAIL_COLOR
+        } else if occupancy.is_wall(&position) {
+            config::PLAYGROUND_WALL_COLOR
+        } else if let Some(apple) = game.apples.iter().find(|apple| apple.position == position) {
+            apple.kind.color()
+        } else {
+            config::PLAYGROUND_GROUND_COLOR
+        }
+    }
+
+    fn render(&self) -> String {
+        let game = &self.session.game;
+        let width = game.settings.board_width as i32;
+        let height = game.settings.board_height as i32;
+
+        let mut frame = String::from("\x1b[H");
+        let wall = background(&config::PLAYGROUND_WALL_COLOR);
+        let wall_row = format!(
+            "{}{}\x1b[0m\x1b[K\r\n",
+            wall,
+            "  ".repeat(width as usize + 2)
+        );
+
+        frame.push_str(&wall_row);
+        for y in 0..height {
+            frame.push_str(&wall);
+            frame.push_str("  ");
+            for x in 0..width {
+                frame.push_str(&background(&self.cell_color(x, y)));
+                frame.push_str("  ");
+            }
+            frame.push_str(&wall);
+            frame.push_str("  \x1b[0m\x1b[K\r\n");
+        }
+        frame.push_str(&wall_row);
+
+        let progress = game.progress();
+        let mut entries = game.mode.status(&progress).into_iter().collect::<Vec<_>>();
+        entries.push((i18n::tr("score"), progress.score.to_string()));
+        entries.push((i18n::tr("length"), progress.length.to_string()));
+        let seconds = progress.elapsed_seconds as u32;
+        entries.push((
+            i18n::tr("time"),
+            format!("{}:{:02}", seconds / 60, seconds % 60),
+        ));
+        entries.push((
+            i18n::tr("best"),
+            self.session
+                .best_score()
+                .map(|score| score.to_string())
+                .unwrap_or_else(|| "-".to_string()),
+        ));
+        let status: Vec<String> = entries
+            .iter()
+            .map(|(label, value)| format!("{}: \x1b[1m{}\x1b[22m", label, value))
+            .collect();
+        push_line(&mut frame, &status.join("   "));
+
+        let effects: Vec<String> = game
+            .effects
+            .iter()
+            .map(|effect| format!("{} ({})", effect.kind.label(), effect.remaining_ticks))
+            .collect();
+        push_line(&mut frame, &effects.join("   "));
+        push_line(&mut frame, "");
+
+        if self.is_choosing_mode {
+            push_line(
+                &mut frame,
+                &format!("\x1b[1m{}\x1b[22m", i18n::tr("app-title")),
+            );
+            push_line(
+                &mut frame,
+                &format!("<   {}   >", GameMode::PRESETS[self.selected_mode]),
+            );
+            push_line(&mut frame, &i18n::tr("mode-selector-hint"));
+            push_line(&mut frame, &i18n::tr("terminal-start-hint"));
+            if self.has_saved_game {
+                push_line(&mut frame, &i18n::tr("terminal-continue-hint"));
+            }
+        } else {
+            match self.state {
+                PlayState::Ready | PlayState::Playing => {}
+                PlayState::Countdown => {
+                    push_line(
+                        &mut frame,
+                        &format!("\x1b[1m{}\x1b[22m", self.countdown.seconds_left()),
+                    );
+                }
+                PlayState::Paused => {
+                    push_line(
+                        &mut frame,
+                        &format!("\x1b[1m{}\x1b[22m", i18n::tr("paused")),
+                    );
+                    push_line(&mut frame, &i18n::tr("paused-hint"));
+                }
+                PlayState::GameOver | PlayState::Victory => {
+                    if let Some(summary) = &self.session.summary {
+                        push_line(&mut frame, &format!("\x1b[1m{}\x1b[22m", summary.title));
+                        push_line(
+                            &mut frame,
+                            &format!("{}: {}", summary.score_label, summary.score),
+                        );
+                        for detail in summary.details.iter() {
+                            push_line(&mut frame, detail);
+                        }
+                    }
+                    let hint = if self.state == PlayState::Victory {
+                        "victory-hint"
+                    } else {
+                        "game-over-hint"
+                    };
+                    push_line(&mut frame, &i18n::tr(hint));
+                }
+            }
+        }
+
+        for (message, _) in self.notices.iter() {
+            push_line(&mut frame, &format!("\x1b[1m{}\x1b[22m", message));
+        }
+
+        // Removes the leftovers of longer frames
+        frame.push_str("\x1b[J");
+
+        frame
+    }
+}
+
+impl StateMachine for TerminalGame {
+    fn state(&self) -> PlayState {
+        self.state
+    }
+
+    fn set_state(&mut self, state: PlayState) {
+        self.state = state;
+    }
+
+    fn enter_state(&mut self, state: PlayState, _previous: PlayState) {
+        match state {
+            PlayState::Countdown => {
+                let seconds = self.session.game.settings.countdown;
+                self.countdown.start(seconds);
+            }
+            PlayState::GameOver | PlayState::Victory => {
+                // The saved game has been played to its end
+                SaveGame::remove();
+            }
+            _ => {}
+        }
+    }
+}
+
+fn background(color: &Color) -> String {
+    // Translucent colors are blended onto the ground like on the canvas
+    let ground = config::PLAYGROUND_GROUND_COLOR;
+    let blend = |value: u8, ground: u8| {
+        (value as f32 * color.a + ground as f32 * (1.0 - color.a)).round() as u8
+    };
+
+    format!(
+        "\x1b[48;2;{};{};{}m",
+        blend(color.r, ground.r),
+        blend(color.g, ground.g),
+        blend(color.b, ground.b)
+    )
+}
+
+fn push_line(frame: &mut String, line: &str) {
+    let _ = write!(frame, "{}\x1b[K\r\n", line);
+}
+
+pub fn run() -> io::Result<()> {
+    let settings = Settings::from_env()
+        .and_then(|settings| settings.validate().map(|_| settings))
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?;
+    i18n::init(&settings.language);
+
+    let mut game = TerminalGame::new(settings);
+    let mut terminal = Terminal::enter()?;
+
+    let update_duration = Duration::from_secs_f64(1.0 / config::UPDATES_PER_SECOND);
+    let mut next_update = Instant::now();
+    let mut last_frame = String::new();
+
+    while !game.is_quit {
+        for input in terminal.read_input()? {
+            game.handle_input(input);
+        }
+
+        game.update();
+
+        let frame = game.render();
+        if frame != last_frame {
+            terminal.write(&frame)?;
+            last_frame = frame;
+        }
+
+        next_update += update_duration;
+        let now = Instant::now();
+        if next_update > now {
+            thread::sleep(next_update - now);
+        } else {
+            // Skips the missed updates instead of catching up in a burst
+            next_update = now;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counts_down_before_the_snake_moves() {
+        let settings = Settings {
+            countdown: 1,
+            ..Settings::default()
+        };
+        let mut game = TerminalGame::new(settings);
+
+        game.handle_input(Input::Enter);
+        assert!(!game.is_choosing_mode);
+        game.update();
+        assert_eq!(game.state, PlayState::Countdown);
+
+        game.handle_input(Input::Char('p'));
+        assert_eq!(game.state, PlayState::Paused);
+        game.handle_input(Input::Char('p'));
+        assert_eq!(game.state, PlayState::Countdown);
+
+        for _ in 0..config::UPDATES_PER_SECOND as u32 {
+            game.update();
+        }
+        assert_eq!(game.state, PlayState::Playing);
+        assert_eq!(game.session.game.ticks, 0);
+    }
+}
@@ -0,0 +1,138 @@
+use std::io::{self, Read, Write};
+use std::mem;
+
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum Input {
+    Up,
+    Down,
+    Left,
+    Right,
+    Escape,
+    Enter,
+    Backspace,
+    Char(char),
+    /// Ctrl-C, which does not raise a signal in raw mode
+    Interrupt,
+}
+
+/// Puts the terminal into raw mode on an alternate screen and restores it
+/// when dropped, also when the game panics
+pub struct Terminal {
+    original: libc::termios,
+    stdout: io::Stdout,
+}
+
+impl Terminal {
+    pub fn enter() -> io::Result<Terminal> {
+        let original = unsafe {
+            let mut termios: libc::termios = mem::zeroed();
+            if libc::tcgetattr(libc::STDIN_FILENO, &mut termios) != 0 {
+                return Err(io::Error::last_os_error());
+            }
+            termios
+        };
+
+        let mut raw = original;
+        unsafe {
+            libc::cfmakeraw(&mut raw);
+        }
+        // Reads return immediately, so that the game keeps running without input
+        raw.c_cc[libc::VMIN] = 0;
+        raw.c_cc[libc::VTIME] = 0;
+        if unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &raw) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        let mut terminal = Terminal {
+            original,
+            stdout: io::stdout(),
+        };
+        terminal.write("\x1b[?1049h\x1b[?25l\x1b[2J")?;
+
+        Ok(terminal)
+    }
+
+    pub fn write(&mut self, content: &str) -> io::Result<()> {
+        self.stdout.write_all(content.as_bytes())?;
+        self.stdout.flush()
+    }
+
+    pub fn read_input(&mut self) -> io::Result<Vec<Input>> {
+        let mut buffer = [0; 64];
+        let count = match io::stdin().read(&mut buffer) {
+            Ok(count) => count,
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => 0,
+            Err(error) => return Err(error),
+        };
+
+        Ok(parse_input(&buffer[..count]))
+    }
+}
+
+impl Drop for Terminal {
+    fn drop(&mut self) {
+        let _ = self.write("\x1b[0m\x1b[?25h\x1b[?1049l");
+        unsafe {
+            libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &self.original);
+        }
+    }
+}
+
+/// Turns the bytes read from the terminal into inputs. Arrow keys arrive as
+/// escape sequences, a lone escape byte is the escape key itself.
+pub fn parse_input(bytes: &[u8]) -> Vec<Input> {
+    let mut inputs = Vec::new();
+
+    let mut index = 0;
+    while index < bytes.len() {
+        let input = match bytes[index] {
+            0x1b => match (bytes.get(index + 1), bytes.get(index + 2)) {
+                (Some(b'[' | b'O'), Some(code)) => {
+                    index += 2;
+                    match code {
+                        b'A' => Some(Input::Up),
+                        b'B' => Some(Input::Down),
+                        b'C' => Some(Input::Right),
+                        b'D' => Some(Input::Left),
+                        _ => None,
+                    }
+                }
+                _ => Some(Input::Escape),
+            },
+            b'\r' | b'\n' => Some(Input::Enter),
+            0x7f | 0x08 => Some(Input::Backspace),
+            0x03 => Some(Input::Interrupt),
+            byte if byte.is_ascii_graphic() || byte == b' ' => {
+                Some(Input::Char(byte.to_ascii_lowercase() as char))
+            }
+            _ => None,
+        };
+
+        inputs.extend(input);
+        index += 1;
+    }
+
+    inputs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_input() {
+        assert_eq!(
+            parse_input(b"\x1b[A\x1bOBwD \r\x1b"),
+            vec![
+                Input::Up,
+                Input::Down,
+                Input::Char('w'),
+                Input::Char('d'),
+                Input::Char(' '),
+                Input::Enter,
+                Input::Escape,
+            ]
+        );
+        assert_eq!(parse_input(b"\x03"), vec![Input::Interrupt]);
+    }
+}